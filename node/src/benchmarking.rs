@@ -23,7 +23,7 @@
 use crate::service::FullClient;
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{AccountId, Balance, BalancesCall, BountiesInstance, GrantsInstance, SystemCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
 use sp_core::{Encode, Pair};
@@ -142,6 +142,10 @@ pub fn create_benchmark_extrinsic(
 		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
 		frame_system::CheckWeight::<runtime::Runtime>::new(),
 		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		pallet_proposal::CheckProposalRate::<runtime::Runtime, GrantsInstance>::new(),
+		pallet_proposal::CheckProposalMembership::<runtime::Runtime, GrantsInstance>::new(),
+		pallet_proposal::CheckProposalRate::<runtime::Runtime, BountiesInstance>::new(),
+		pallet_proposal::CheckProposalMembership::<runtime::Runtime, BountiesInstance>::new(),
 	);
 
 	let raw_payload = runtime::SignedPayload::from_raw(
@@ -156,6 +160,10 @@ pub fn create_benchmark_extrinsic(
 			(),
 			(),
 			(),
+			(),
+			(),
+			(),
+			(),
 		),
 	);
 	let signature = raw_payload.using_encoded(|e| sender.sign(e));
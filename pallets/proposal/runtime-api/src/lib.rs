@@ -0,0 +1,63 @@
+//! Runtime APIs to page through the proposals and donations of pallet-proposal.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_proposal::{Donation, ProposalIndex, ProposalStatus};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Lists proposals from the secondary indexes of the pallet.
+	///
+	/// `Program` identifies the instance of the pallet to read, as the runtime names them.
+	/// Every method returns up to `limit` proposals, as `(index, hash)` pairs in ascending
+	/// order of index, starting with the first proposal whose index is at least `start`.
+	/// The next page starts right after the last index returned.
+	pub trait ProposalApi<AccountId, Hash, Program>
+	where
+		AccountId: Codec,
+		Hash: Codec,
+		Program: Codec,
+	{
+		/// Proposals submitted by `who`.
+		fn proposals_by_proposer(
+			program: Program,
+			who: AccountId,
+			start: ProposalIndex,
+			limit: u32,
+		) -> Vec<(ProposalIndex, Hash)>;
+
+		/// Proposals paying `who`.
+		fn proposals_by_beneficiary(
+			program: Program,
+			who: AccountId,
+			start: ProposalIndex,
+			limit: u32,
+		) -> Vec<(ProposalIndex, Hash)>;
+
+		/// Proposals in the given status.
+		fn proposals_by_status(
+			program: Program,
+			status: ProposalStatus,
+			start: ProposalIndex,
+			limit: u32,
+		) -> Vec<(ProposalIndex, Hash)>;
+	}
+
+	/// Reads the donations made to the pot of the pallet.
+	///
+	/// `Program` identifies the instance of the pallet to read, as the runtime names them.
+	pub trait DonationApi<AccountId, Balance, Hash, BlockNumber, Program>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+		Hash: Codec,
+		BlockNumber: Codec,
+		Program: Codec,
+	{
+		/// Donations made by `who`, oldest first.
+		fn donations(program: Program, who: AccountId) -> Vec<Donation<Balance, Hash, BlockNumber>>;
+
+		/// Up to `limit` donors with their total donated, largest total first.
+		fn top_donors(program: Program, limit: u32) -> Vec<(AccountId, Balance)>;
+	}
+}
@@ -0,0 +1,243 @@
+use crate::{Call, Commitments, Config, Error, Pallet};
+use frame_support::{
+	codec::{Decode, Encode},
+	sp_runtime::{
+		traits::{DispatchInfoOf, SignedExtension},
+		transaction_validity::{
+			InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+			ValidTransaction,
+		},
+	},
+	sp_std::{fmt, marker::PhantomData},
+	traits::{Get, IsSubType, SortedMembers},
+};
+use scale_info::TypeInfo;
+
+/// Drops proposal submissions exceeding the rate limits of the pallet from the transaction pool.
+///
+/// A submission is invalid when the member has too many open proposals, submitted a proposal
+/// less than `SubmissionGap` blocks ago, or submits the document of a proposal rejected less
+/// than `ResubmissionCooldown` blocks ago.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T, I))]
+pub struct CheckProposalRate<T: Config<I> + Send + Sync, I: 'static + Send + Sync = ()>(
+	PhantomData<(T, I)>,
+);
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> CheckProposalRate<T, I> {
+	/// Custom validity error of a member with too many open proposals.
+	pub const TOO_MANY_OPEN_PROPOSALS: u8 = 0;
+	/// Custom validity error of a member submitting proposals too close to each other.
+	pub const SUBMISSION_TOO_SOON: u8 = 1;
+	/// Custom validity error of a document rejected recently.
+	pub const RESUBMISSION_COOLDOWN: u8 = 2;
+
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> Default for CheckProposalRate<T, I> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> fmt::Debug for CheckProposalRate<T, I> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckProposalRate")
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> SignedExtension
+	for CheckProposalRate<T, I>
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T, I>>,
+{
+	const IDENTIFIER: &'static str = "CheckProposalRate";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		let document = match call.is_sub_type() {
+			Some(Call::add_proposal { document, .. })
+			| Some(Call::add_call_proposal { document, .. }) => document,
+			_ => return Ok(ValidTransaction::default()),
+		};
+
+		Pallet::<T, I>::ensure_can_submit(who, document).map_err(|error| {
+			let code = match error {
+				Error::<T, I>::TooManyOpenProposals => Self::TOO_MANY_OPEN_PROPOSALS,
+				Error::<T, I>::SubmissionTooSoon => Self::SUBMISSION_TOO_SOON,
+				_ => Self::RESUBMISSION_COOLDOWN,
+			};
+			InvalidTransaction::Custom(code)
+		})?;
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+}
+
+/// Drops proposal and vote calls which would fail for lack of membership from the transaction
+/// pool, before their sender pays any fee.
+///
+/// Proposals are only valid from community members, votes and recusals only from committee
+/// members on an existing open proposal they didn't vote on or recuse from yet, and votes only
+/// in the ballot mode of the pallet, public or secret. Committee votes submitted
+/// within `UrgentVoteWindow` blocks of the end of the voting phase get `UrgentVotePriority`.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T, I))]
+pub struct CheckProposalMembership<T: Config<I> + Send + Sync, I: 'static + Send + Sync = ()>(
+	PhantomData<(T, I)>,
+);
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> CheckProposalMembership<T, I> {
+	/// Custom validity error of a proposal from an account outside the community.
+	pub const NOT_COMMUNITY_MEMBER: u8 = 3;
+	/// Custom validity error of a vote from an account outside the committee.
+	pub const NOT_COMMITTEE_MEMBER: u8 = 4;
+	/// Custom validity error of a call on an unknown proposal.
+	pub const PROPOSAL_MISSING: u8 = 5;
+	/// Custom validity error of a member voting twice on a proposal.
+	pub const ALREADY_VOTED: u8 = 6;
+	/// Custom validity error of a vote on a settled proposal.
+	pub const PROPOSAL_NOT_OPEN: u8 = 7;
+	/// Custom validity error of a vote from a member with a conflict of interest or recused.
+	pub const NOT_IN_ELECTORATE: u8 = 8;
+	/// Custom validity error of a public vote while ballots are secret, or the other way around.
+	pub const WRONG_BALLOT: u8 = 9;
+
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+
+	fn vote_error(error: Error<T, I>) -> InvalidTransaction {
+		InvalidTransaction::Custom(match error {
+			Error::<T, I>::MemberIsNotPresentInCommittee => Self::NOT_COMMITTEE_MEMBER,
+			Error::<T, I>::AlreadyApproved => Self::ALREADY_VOTED,
+			Error::<T, I>::ProposalMissing => Self::PROPOSAL_MISSING,
+			Error::<T, I>::ProposalNotOpen => Self::PROPOSAL_NOT_OPEN,
+			_ => Self::NOT_IN_ELECTORATE,
+		})
+	}
+
+	/// Priority of a vote in a phase ending at `deadline`.
+	fn vote_priority(deadline: Option<T::BlockNumber>) -> TransactionPriority {
+		let now = frame_system::Pallet::<T>::block_number();
+		match deadline {
+			Some(deadline) if now + T::UrgentVoteWindow::get().into() >= deadline =>
+				T::UrgentVotePriority::get(),
+			_ => 0,
+		}
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> Default
+	for CheckProposalMembership<T, I>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> fmt::Debug
+	for CheckProposalMembership<T, I>
+{
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckProposalMembership")
+	}
+}
+
+impl<T: Config<I> + Send + Sync, I: 'static + Send + Sync> SignedExtension
+	for CheckProposalMembership<T, I>
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<Call<T, I>>,
+{
+	const IDENTIFIER: &'static str = "CheckProposalMembership";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		let priority = match call.is_sub_type() {
+			Some(Call::add_proposal { .. }) | Some(Call::add_call_proposal { .. }) => {
+				// member should be present in community members list
+				if !T::CommunityProvider::contains(who) {
+					return Err(InvalidTransaction::Custom(Self::NOT_COMMUNITY_MEMBER).into())
+				}
+				0
+			},
+			Some(Call::approve_proposal { proposal, .. }) => {
+				if T::SecretBallots::get() {
+					return Err(InvalidTransaction::Custom(Self::WRONG_BALLOT).into())
+				}
+				let proposal_hash = Pallet::<T, I>::resolve(*proposal)
+					.map_err(|_| InvalidTransaction::Custom(Self::PROPOSAL_MISSING))?;
+				Pallet::<T, I>::ensure_can_vote(who, &proposal_hash).map_err(Self::vote_error)?;
+				Self::vote_priority(Pallet::<T, I>::voting_deadline(proposal_hash))
+			},
+			Some(Call::commit_vote { proposal, .. }) => {
+				if !T::SecretBallots::get() {
+					return Err(InvalidTransaction::Custom(Self::WRONG_BALLOT).into())
+				}
+				let proposal_hash = Pallet::<T, I>::resolve(*proposal)
+					.map_err(|_| InvalidTransaction::Custom(Self::PROPOSAL_MISSING))?;
+				Pallet::<T, I>::ensure_can_vote(who, &proposal_hash).map_err(Self::vote_error)?;
+				if Commitments::<T, I>::contains_key(proposal_hash, who) {
+					return Err(InvalidTransaction::Custom(Self::ALREADY_VOTED).into())
+				}
+				Self::vote_priority(Pallet::<T, I>::reveal_start(proposal_hash))
+			},
+			Some(Call::recuse { proposal }) => {
+				let proposal_hash = Pallet::<T, I>::resolve(*proposal)
+					.map_err(|_| InvalidTransaction::Custom(Self::PROPOSAL_MISSING))?;
+				Pallet::<T, I>::ensure_can_recuse(who, &proposal_hash).map_err(Self::vote_error)?;
+				0
+			},
+			_ => 0,
+		};
+		Ok(ValidTransaction { priority, ..Default::default() })
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		self.validate(who, call, info, len).map(|_| ())
+	}
+}
@@ -7,122 +7,900 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(test)]
+mod proptests;
+
+mod extensions;
+pub use extensions::{CheckProposalMembership, CheckProposalRate};
+
 // #[cfg(feature = "runtime-benchmarks")]
 // mod benchmarking;
 
 use frame_support::{
-	codec::{Decode, Encode},
-	dispatch::DispatchResult,
+	codec::{Decode, Encode, FullEncode, MaxEncodedLen},
+	dispatch::{DispatchError, DispatchResult, Dispatchable},
+	ensure,
 	inherent::Vec,
-	sp_runtime::RuntimeDebug,
-	traits::{Currency, ExistenceRequirement},
+	sp_io::hashing::blake2_256,
+	sp_runtime::{
+		traits::{Hash as _, IntegerSquareRoot, SaturatedConversion, Saturating, Zero},
+		PerThing, Perbill, Perquintill, RuntimeDebug,
+	},
+	sp_std::{cmp::Reverse, collections::btree_map::BTreeMap, marker::PhantomData},
+	storage::{with_storage_layer, StorageMap},
+	traits::{
+		schedule::{self, v3::TaskName, DispatchTime},
+		BalanceStatus, Bounded, ChangeMembers, Contains, Currency, EnsureOrigin,
+		ExistenceRequirement, Get, InitializeMembers, PalletInfoAccess, PreimageProvider,
+		ReservableCurrency, SortedMembers,
+	},
+	weights::Weight,
 };
-use scale_info::TypeInfo;
+use scale_info::{prelude::vec, TypeInfo};
 
 pub type MemberCount = u32;
+
+/// Sequential number of a proposal.
+pub type ProposalIndex = u32;
 pub type ProposalId<T> = <T as frame_system::Config>::Hash;
 
+/// Sequential number of a funding round.
+pub type RoundIndex = u32;
+
+/// Scale of the square roots of the quadratic funding, which keep three decimals.
+const ROOT_SCALE: u128 = 1_000_000;
+
+/// Weight of exploring one amount for one proposal in the knapsack of a budget round.
+const KNAPSACK_STEP_WEIGHT: u64 = 100_000;
+
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct Votes<AccountId> {
 	ayes: Vec<AccountId>,
 	nays: Vec<AccountId>,
+	abstains: Vec<AccountId>,
+}
+
+impl<AccountId> Votes<AccountId> {
+	/// Count the votes cast so far.
+	pub fn tally(&self) -> Tally {
+		Tally {
+			ayes: self.ayes.len() as MemberCount,
+			nays: self.nays.len() as MemberCount,
+			abstains: self.abstains.len() as MemberCount,
+		}
+	}
 }
 
 #[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
-pub struct ProposalInfo<Balance> {
+pub struct ProposalInfo<AccountId, Balance, Hash> {
+	proposer: AccountId,
 	title: Vec<u8>,
 	amount: Balance,
+	status: ProposalStatus,
+	/// Sequential number of the proposal.
+	index: ProposalIndex,
+	/// Hash of the document describing the proposal.
+	document: Hash,
+	/// Number of amendments made to the proposal.
+	version: u32,
+	/// Funds donated to the proposal, escrowed until it is paid.
+	escrowed: Balance,
+	/// Part of the amount left for the pot to pay.
+	gap: Balance,
+}
+
+/// Terms of a proposal before one of its amendments.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ProposalVersion<AccountId, Balance, Hash, BlockNumber> {
+	pub version: u32,
+	pub amount: Balance,
+	pub payees: Vec<(AccountId, Perbill)>,
+	pub document: Hash,
+	/// Block at which these terms were replaced.
+	pub amended_at: BlockNumber,
+}
+
+/// Summary of the terms changed by an amendment.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct AmendmentDiff<Balance, Hash> {
+	/// Previous and new amount.
+	pub amount: Option<(Balance, Balance)>,
+	pub payees_changed: bool,
+	/// Previous and new document hash.
+	pub document: Option<(Hash, Hash)>,
+	pub votes_reset: bool,
+}
+
+/// A quadratic funding round, matching the contributions made to proposals between its start
+/// and its end.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct FundingRound<AccountId, Balance, BlockNumber> {
+	pub start: BlockNumber,
+	/// First block at which contributions are refused and the round can be closed.
+	pub end: BlockNumber,
+	/// Pot account the matching pool is reserved from until the round closes.
+	pub pot: AccountId,
+	/// Funds of the pot shared between the proposals of the round.
+	pub matching_pool: Balance,
+}
+
+/// A donation made to the pot.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Donation<Balance, Hash, BlockNumber> {
+	pub amount: Balance,
+	/// Hash of a note attached by the donor.
+	pub memo: Option<Hash>,
+	pub block: BlockNumber,
+}
+
+/// Reference to a proposal, either by its index or by its hash.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum ProposalRef<Hash> {
+	Index(ProposalIndex),
+	Hash(Hash),
+}
+
+impl<Hash> From<Hash> for ProposalRef<Hash> {
+	fn from(hash: Hash) -> Self {
+		ProposalRef::Hash(hash)
+	}
+}
+
+/// What happens to the votes already cast on a proposal when it is amended.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum AmendmentPolicy {
+	/// The votes are dropped and the committee votes again on the new terms.
+	ResetVotes,
+	/// The votes cast so far still count.
+	KeepVotes,
+}
+
+/// How the approved proposals are ranked for a budget round.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum BudgetRanking {
+	/// By the share of the electorate which approved the proposal.
+	ApprovalScore,
+	/// In the order set by the `RankingOrigin`, then by approval score.
+	CommitteeRanking,
+}
+
+/// How the budget of a round is allocated to the ranked proposals.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum BudgetAllocation {
+	/// In rank order, every proposal which still fits in the budget is funded.
+	Greedy,
+	/// The proposals spending the most of the budget are funded, whatever their rank.
+	Knapsack,
+}
+
+/// Lifecycle of a proposal, from its submission until it is settled.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum ProposalStatus {
+	/// The committee is voting on the proposal.
+	Open,
+	/// The committee approved the proposal, the payout is pending.
+	Approved,
+	/// The committee didn't approve the proposal.
+	Rejected,
+	/// Not enough committee members voted on the proposal.
+	QuorumNotReached,
+	/// The requested funds were transferred to the beneficiary.
+	Paid,
+	/// The call carried by the proposal was dispatched.
+	Executed,
+	/// The committee approved the proposal but it didn't fit in the budget of a round, it rolls
+	/// over to the next one.
+	Deferred,
+}
+
+/// Number of votes cast on a proposal.
+#[derive(
+	PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct Tally {
+	pub ayes: MemberCount,
+	pub nays: MemberCount,
+	pub abstains: MemberCount,
+}
+
+impl Tally {
+	/// Number of members who voted, abstentions included.
+	pub fn turnout(&self) -> MemberCount {
+		self.ayes.saturating_add(self.nays).saturating_add(self.abstains)
+	}
+}
+
+/// Handler for the status transitions of proposals.
+/// It is implemented for tuples, so that a runtime can compose several handlers.
+pub trait OnProposalOutcome<AccountId, Hash, Balance> {
+	/// Called every time a proposal moves to a new status.
+	fn on_proposal_outcome(
+		proposal_hash: &Hash,
+		status: ProposalStatus,
+		beneficiary: &AccountId,
+		amount: Balance,
+		tally: &Tally,
+	);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, Hash, Balance: Copy> OnProposalOutcome<AccountId, Hash, Balance> for Tuple {
+	fn on_proposal_outcome(
+		proposal_hash: &Hash,
+		status: ProposalStatus,
+		beneficiary: &AccountId,
+		amount: Balance,
+		tally: &Tally,
+	) {
+		for_tuples!( #( Tuple::on_proposal_outcome(proposal_hash, status, beneficiary, amount, tally); )* );
+	}
+}
+
+/// Schedules the settlement of proposals at the end of their voting period.
+pub trait ScheduleSettlement<BlockNumber, Hash> {
+	/// Schedule the settlement of the proposal at block `when`, replacing any earlier schedule.
+	/// Returns false when the proposal should be settled by the `TransferTime` hook instead.
+	fn schedule(proposal_hash: &Hash, when: BlockNumber) -> bool;
+
+	/// Cancel the scheduled settlement of the proposal, if any.
+	fn cancel(proposal_hash: &Hash);
+}
+
+/// The legacy settlement, through the `TransferTime` hook of the pallet.
+impl<BlockNumber, Hash> ScheduleSettlement<BlockNumber, Hash> for () {
+	fn schedule(_proposal_hash: &Hash, _when: BlockNumber) -> bool {
+		false
+	}
+
+	fn cancel(_proposal_hash: &Hash) {}
+}
+
+/// Settles proposals through a named task of `Scheduler`, keyed by the proposal hash, which
+/// dispatches `settle` with the root origin.
+pub struct SchedulerSettlement<T, Scheduler, PalletsOrigin, I = ()>(
+	PhantomData<(T, Scheduler, PalletsOrigin, I)>,
+);
+
+impl<T, Scheduler, PalletsOrigin, I> SchedulerSettlement<T, Scheduler, PalletsOrigin, I>
+where
+	T: Config<I>,
+	I: 'static,
+{
+	/// Name of the settlement task of a proposal, distinct for every instance of the pallet.
+	pub fn task_name(proposal_hash: &T::Hash) -> TaskName {
+		let pallet = <Pallet<T, I> as PalletInfoAccess>::name();
+		(b"proposal/settle", pallet, proposal_hash).using_encoded(blake2_256)
+	}
+}
+
+impl<T, Scheduler, PalletsOrigin, I> ScheduleSettlement<T::BlockNumber, T::Hash>
+	for SchedulerSettlement<T, Scheduler, PalletsOrigin, I>
+where
+	T: Config<I>,
+	I: 'static,
+	Scheduler: schedule::v3::Named<T::BlockNumber, <T as Config<I>>::RuntimeCall, PalletsOrigin>,
+	PalletsOrigin: From<frame_system::RawOrigin<T::AccountId>>,
+{
+	fn schedule(proposal_hash: &T::Hash, when: T::BlockNumber) -> bool {
+		let call: <T as Config<I>>::RuntimeCall =
+			// Calls are not dispatched by the scheduler, they wait for someone to close them.
+			Call::<T, I>::settle { proposal_hash: *proposal_hash, call_weight_bound: Weight::zero() }
+				.into();
+		let call = match call.encode().try_into() {
+			Ok(call) => Bounded::Inline(call),
+			Err(_) => return false,
+		};
+		let name = Self::task_name(proposal_hash);
+		// Replace the task of an earlier voting period.
+		let _ = Scheduler::cancel_named(name);
+		Scheduler::schedule_named(
+			name,
+			DispatchTime::At(when),
+			None,
+			schedule::HARD_DEADLINE,
+			frame_system::RawOrigin::Root.into(),
+			call,
+		)
+		.is_ok()
+	}
+
+	fn cancel(proposal_hash: &T::Hash) {
+		let _ = Scheduler::cancel_named(Self::task_name(proposal_hash));
+	}
+}
+
+/// Community members kept by the pallet.
+pub struct Community<T, I = ()>(PhantomData<(T, I)>);
+impl<T: Config<I>, I: 'static> SortedMembers<T::AccountId> for Community<T, I> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		CommunityMembers::<T, I>::get()
+	}
+
+	fn contains(who: &T::AccountId) -> bool {
+		CommunityMembers::<T, I>::get().binary_search(who).is_ok()
+	}
+
+	fn count() -> usize {
+		CommunityMembers::<T, I>::decode_len().unwrap_or(0)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add(who: &T::AccountId) {
+		CommunityMembers::<T, I>::mutate(|members| {
+			if let Err(location) = members.binary_search(who) {
+				members.insert(location, who.clone());
+			}
+		});
+	}
+}
+
+impl<T: Config<I>, I: 'static> Contains<T::AccountId> for Community<T, I> {
+	fn contains(who: &T::AccountId) -> bool {
+		<Self as SortedMembers<T::AccountId>>::contains(who)
+	}
+}
+
+/// Committee members kept by the pallet, elected by the community or set by a membership pallet
+/// through `ChangeMembers`.
+pub struct Committee<T, I = ()>(PhantomData<(T, I)>);
+impl<T: Config<I>, I: 'static> SortedMembers<T::AccountId> for Committee<T, I> {
+	fn sorted_members() -> Vec<T::AccountId> {
+		CommitteeMembers::<T, I>::get()
+	}
+
+	fn contains(who: &T::AccountId) -> bool {
+		CommitteeMembers::<T, I>::get().binary_search(who).is_ok()
+	}
+
+	fn count() -> usize {
+		CommitteeMembers::<T, I>::decode_len().unwrap_or(0)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn add(who: &T::AccountId) {
+		CommitteeMembers::<T, I>::mutate(|members| {
+			if let Err(location) = members.binary_search(who) {
+				members.insert(location, who.clone());
+			}
+		});
+	}
+}
+
+impl<T: Config<I>, I: 'static> Contains<T::AccountId> for Committee<T, I> {
+	fn contains(who: &T::AccountId) -> bool {
+		<Self as SortedMembers<T::AccountId>>::contains(who)
+	}
+}
+
+/// Lets `pallet_membership` or an election pallet set the committee.
+impl<T: Config<I>, I: 'static> ChangeMembers<T::AccountId> for Pallet<T, I> {
+	fn change_members_sorted(
+		_incoming: &[T::AccountId],
+		_outgoing: &[T::AccountId],
+		new: &[T::AccountId],
+	) {
+		CommitteeMembers::<T, I>::put(new);
+		Self::deposit_event(Event::NewTerm { members: new.to_vec() });
+	}
+}
+
+impl<T: Config<I>, I: 'static> InitializeMembers<T::AccountId> for Pallet<T, I> {
+	fn initialize_members(members: &[T::AccountId]) {
+		if !members.is_empty() {
+			assert!(
+				CommitteeMembers::<T, I>::get().is_empty(),
+				"Committee is already initialized!"
+			);
+			CommitteeMembers::<T, I>::put(members);
+		}
+	}
+}
+
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Application<AccountId, Balance, Hash> {
+	deposit: Balance,
+	metadata: Hash,
+	vouchers: Vec<AccountId>,
 }
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum Vote {
 	Aye,
 	Nay,
+	/// Counts toward the turnout but not toward the approval.
+	Abstain,
+}
+
+/// Origin of the calls dispatched by approved proposals.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(I))]
+#[codec(mel_bound(Hash: MaxEncodedLen))]
+pub enum RawOrigin<Hash, I> {
+	/// The call was approved by the committee through the proposal with the given hash.
+	Proposal(Hash),
+	/// Dummy to manage the fact we have instancing.
+	_Phantom(PhantomData<I>),
+}
+
+/// Ensure the origin is a call dispatched by an approved proposal, returns the proposal hash.
+pub struct EnsureProposal<T, I = ()>(PhantomData<(T, I)>);
+impl<T: Config<I>, I: 'static, O> EnsureOrigin<O> for EnsureProposal<T, I>
+where
+	O: Into<Result<RawOrigin<T::Hash, I>, O>> + From<RawOrigin<T::Hash, I>>,
+{
+	type Success = T::Hash;
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Proposal(proposal_hash) => Ok(proposal_hash),
+			r => Err(O::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<O, ()> {
+		Ok(O::from(RawOrigin::Proposal(Default::default())))
+	}
 }
 
 #[frame_support::pallet]
 pub mod pallet {
-	use crate::{MemberCount, ProposalId, ProposalInfo, Vote, Votes};
+	use crate::{
+		AmendmentDiff, AmendmentPolicy, Application, BudgetAllocation, BudgetRanking, Donation,
+		FundingRound, MemberCount, OnProposalOutcome, ProposalId, ProposalIndex, ProposalInfo,
+		ProposalRef, ProposalStatus, ProposalVersion, RawOrigin, RoundIndex, ScheduleSettlement,
+		Vote, Votes,
+	};
 	use frame_support::{
+		dispatch::{Dispatchable, GetDispatchInfo, Pays, PostDispatchInfo},
 		inherent::Vec,
 		pallet_prelude::*,
-		traits::{Currency, ExistenceRequirement, ReservableCurrency},
+		sp_runtime::{
+			traits::{Hash, Saturating, Zero},
+			transaction_validity::TransactionPriority,
+			Perbill,
+		},
+		traits::{
+			Currency, ExistenceRequirement, PreimageProvider, ReservableCurrency, SortedMembers,
+		},
 	};
 	use frame_system::pallet_prelude::*;
-	use scale_info::prelude::vec;
+	use scale_info::prelude::{boxed::Box, vec};
+
+	/// The storage layout of the pallet. Version 1 keys the proposals by an index-derived hash
+	/// and adds the secondary indexes; no chain ran version 0, so there is no migration.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	#[pallet::without_storage_info]
-	pub struct Pallet<T>(_);
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T, I = ()>(_);
 
-	pub type BalanceIn<Runtime> = <<Runtime as Config>::Currency as Currency<
+	pub type BalanceIn<Runtime, I = ()> = <<Runtime as Config<I>>::Currency as Currency<
 		<Runtime as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	pub type ProposalInfoOf<Runtime, I = ()> = ProposalInfo<
+		<Runtime as frame_system::Config>::AccountId,
+		BalanceIn<Runtime, I>,
+		<Runtime as frame_system::Config>::Hash,
+	>;
+
+	pub type DonationOf<Runtime, I = ()> = Donation<
+		BalanceIn<Runtime, I>,
+		<Runtime as frame_system::Config>::Hash,
+		<Runtime as frame_system::Config>::BlockNumber,
+	>;
+
+	pub type FundingRoundOf<Runtime, I = ()> = FundingRound<
+		<Runtime as frame_system::Config>::AccountId,
+		BalanceIn<Runtime, I>,
+		<Runtime as frame_system::Config>::BlockNumber,
+	>;
+
+	pub type ProposalVersionOf<Runtime, I = ()> = ProposalVersion<
+		<Runtime as frame_system::Config>::AccountId,
+		BalanceIn<Runtime, I>,
+		<Runtime as frame_system::Config>::Hash,
+		<Runtime as frame_system::Config>::BlockNumber,
+	>;
+
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config<I: 'static = ()>: frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
-		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		type RuntimeEvent: From<Event<Self, I>>
+			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type Currency: ReservableCurrency<Self::AccountId>;
 
 		type TimeDuration: Get<u32>;
+
+		/// Amount reserved from a community member standing for a committee seat.
+		type CandidacyBond: Get<BalanceIn<Self, I>>;
+
+		/// Number of blocks a committee serves before the next election is counted.
+		type TermDuration: Get<u32>;
+
+		/// Number of committee seats filled at each election.
+		type DesiredMembers: Get<u32>;
+
+		/// Amount reserved from an applicant until the membership application is decided.
+		type ApplicationDeposit: Get<BalanceIn<Self, I>>;
+
+		/// Number of vouches from community members which admits an applicant.
+		type VouchThreshold: Get<u32>;
+
+		/// Origin which can add community members and decide on membership applications.
+		type AdmissionOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// The outer origin, which should be able to represent a call approved by a proposal.
+		type RuntimeOrigin: From<RawOrigin<Self::Hash, I>>;
+
+		/// The outer call which approved proposals can dispatch.
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as Config<I>>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + From<frame_system::Call<Self>>
+			+ From<Call<Self, I>>
+			+ GetDispatchInfo;
+
+		/// Maximum encoded length of a call carried by a proposal.
+		type MaxCallLength: Get<u32>;
+
+		/// Handler called when a proposal is approved, rejected, paid or executed.
+		type OnProposalOutcome: OnProposalOutcome<Self::AccountId, Self::Hash, BalanceIn<Self, I>>;
+
+		/// Maximum number of accounts sharing the funds of a proposal.
+		type MaxPayees: Get<u32>;
+
+		/// Part of the committee which has to vote, abstentions included, before a proposal can
+		/// pass.
+		type Quorum: Get<Perbill>;
+
+		/// Whether committee members commit secret votes and reveal them later on, instead of
+		/// voting publicly.
+		type SecretBallots: Get<bool>;
+
+		/// Number of blocks, from the submission of a proposal, during which votes are committed.
+		type CommitDuration: Get<u32>;
+
+		/// Number of blocks, after the commit phase, during which votes are revealed.
+		type RevealDuration: Get<u32>;
+
+		/// Amount reserved from a committee member committing a vote, slashed when the vote isn't
+		/// revealed.
+		type CommitDeposit: Get<BalanceIn<Self, I>>;
+
+		/// Whether the votes already cast on a proposal are kept or reset when it is amended.
+		type AmendmentPolicy: Get<AmendmentPolicy>;
+
+		/// Maximum number of open proposals a member can have at once.
+		type MaxOpenProposals: Get<u32>;
+
+		/// Minimum number of blocks between two submissions of the same member.
+		type SubmissionGap: Get<u32>;
+
+		/// Number of blocks after which the document of a rejected proposal can be submitted
+		/// again.
+		type ResubmissionCooldown: Get<u32>;
+
+		/// Number of blocks before the end of a voting phase during which committee votes are
+		/// urgent.
+		type UrgentVoteWindow: Get<u32>;
+
+		/// Transaction priority added to urgent committee votes.
+		type UrgentVotePriority: Get<TransactionPriority>;
+
+		/// Maximum number of fee-free votes of a committee member in a block.
+		type MaxFreeVotes: Get<u32>;
+
+		/// Schedules the settlement of proposals, `()` settles them through the legacy
+		/// `TransferTime` hook.
+		type Settlement: ScheduleSettlement<Self::BlockNumber, Self::Hash>;
+
+		/// Provider of the proposal documents, which are noted as preimages by the proposers.
+		type Preimages: PreimageProvider<Self::Hash>;
+
+		/// Maximum length of the title of a proposal.
+		type MaxTitleLength: Get<u32>;
+
+		/// Members of the community, `Community<Self>` for the members kept by this pallet.
+		type CommunityProvider: SortedMembers<Self::AccountId>;
+
+		/// Members of the committee, `Committee<Self>` for the members kept by this pallet.
+		type CommitteeProvider: SortedMembers<Self::AccountId>;
+
+		/// Whether the `CommitteeProvider` keeps the committee outside this pallet. The committee
+		/// extrinsics and elections of the pallet are then refused.
+		type CommitteeIsExternal: Get<bool>;
+
+		/// Number of blocks between two budget rounds, which pay the approved proposals within
+		/// the balance of the pot. Approved proposals are paid right away when it is zero.
+		type BudgetPeriod: Get<u32>;
+
+		/// How the approved proposals are ranked for a budget round.
+		type BudgetRanking: Get<BudgetRanking>;
+
+		/// How the budget of a round is allocated to the ranked proposals.
+		type BudgetAllocation: Get<BudgetAllocation>;
+
+		/// Origin which can rank the approved proposals for the budget rounds.
+		type RankingOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// Maximum number of proposals a budget round considers, and ranked by the
+		/// `RankingOrigin`. The proposals ranked after wait for the next round.
+		type MaxRoundProposals: Get<u32>;
 	}
 
+	#[pallet::origin]
+	pub type Origin<T, I = ()> = RawOrigin<<T as frame_system::Config>::Hash, I>;
+
+	/// Proposals settled by the hook at a block, when the `Settlement` doesn't schedule them.
 	#[pallet::storage]
 	#[pallet::getter(fn transfer_time)]
-	pub type TransferTime<T: Config> =
-		StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, ProposalId<T>, ValueQuery>;
+	pub type TransferTime<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		Blake2_128Concat,
+		ProposalId<T>,
+		(),
+		OptionQuery,
+	>;
 
-	/// Accounts which raised proposal to take funds
+	/// Committee members who recused themselves from voting on a proposal, sorted by account.
+	/// Recusals are kept once the proposal is pruned, as an audit trail.
 	#[pallet::storage]
-	#[pallet::getter(fn fund_seeker_accounts)]
-	pub type FundSeekerAccounts<T: Config> =
+	#[pallet::getter(fn recusals)]
+	pub type Recusals<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, T::Hash, Vec<T::AccountId>, ValueQuery>;
 
-	/// list of community members,Anyone can join in this list
+	/// Previous terms of amended proposals, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_versions)]
+	pub type ProposalVersions<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, Vec<ProposalVersionOf<T, I>>, ValueQuery>;
+
+	/// Secret votes committed on a proposal, along with the deposit reserved for them.
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub type Commitments<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		Blake2_128Concat,
+		T::AccountId,
+		(T::Hash, BalanceIn<T, I>),
+		OptionQuery,
+	>;
+
+	/// Block from which the secret votes on a proposal are revealed.
+	#[pallet::storage]
+	#[pallet::getter(fn reveal_start)]
+	pub type RevealStart<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, BlockNumberFor<T>, OptionQuery>;
+
+	/// Block after which the votes on a proposal are settled, set by the first vote or, with
+	/// secret ballots, at the submission of the proposal.
+	#[pallet::storage]
+	#[pallet::getter(fn voting_deadline)]
+	pub type VotingDeadline<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, BlockNumberFor<T>, OptionQuery>;
+
+	/// Accounts which receive the funds of a proposal, along with their share of the amount.
+	/// The first account is the main beneficiary and receives the rounding dust.
+	#[pallet::storage]
+	#[pallet::getter(fn fund_seeker_accounts)]
+	pub type FundSeekerAccounts<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, Vec<(T::AccountId, Perbill)>, ValueQuery>;
+
+	/// list of community members, accounts join through an application or the admission origin.
+	/// Provided by `Community<T>`.
 	#[pallet::storage]
 	#[pallet::getter(fn community_members)]
-	pub type CommunityMembers<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+	pub type CommunityMembers<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Vec<T::AccountId>, ValueQuery>;
 
 	/// Members from community can join in committee list, action should be perform from sudo.
+	/// Provided by `Committee<T>`, and set by `ChangeMembers` and `InitializeMembers`.
 	#[pallet::storage]
 	#[pallet::getter(fn committee_members)]
-	pub type CommitteeMembers<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+	pub type CommitteeMembers<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Vec<T::AccountId>, ValueQuery>;
 
 	/// Count the approval for any particular proposal.
 	#[pallet::storage]
 	#[pallet::getter(fn voting)]
-	pub type Voting<T: Config> = StorageMap<_, Identity, T::Hash, Votes<T::AccountId>, OptionQuery>;
+	pub type Voting<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, T::Hash, Votes<T::AccountId>, OptionQuery>;
+
+	/// Number of proposals submitted so far, the index of the next proposal.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_count)]
+	pub type ProposalCount<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, ProposalIndex, ValueQuery>;
+
+	/// Hash of the proposal submitted with a given index.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_hashes)]
+	pub type ProposalHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, ProposalIndex, T::Hash, OptionQuery>;
+
+	/// Indices of the proposals submitted by an account, in ascending order.
+	#[pallet::storage]
+	#[pallet::getter(fn proposals_by_proposer)]
+	pub type ProposalsByProposer<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<ProposalIndex>, ValueQuery>;
+
+	/// Indices of the proposals paying an account, in ascending order.
+	#[pallet::storage]
+	#[pallet::getter(fn proposals_by_beneficiary)]
+	pub type ProposalsByBeneficiary<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<ProposalIndex>, ValueQuery>;
+
+	/// Indices of the proposals in a given status, in ascending order.
+	#[pallet::storage]
+	#[pallet::getter(fn proposals_by_status)]
+	pub type ProposalsByStatus<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, ProposalStatus, Vec<ProposalIndex>, ValueQuery>;
+
+	/// Number of open proposals submitted by a member.
+	#[pallet::storage]
+	#[pallet::getter(fn open_proposals)]
+	pub type OpenProposals<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Block of the last proposal submitted by a member.
+	#[pallet::storage]
+	#[pallet::getter(fn last_submission)]
+	pub type LastSubmission<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	/// Block at which a proposal with a given document was last rejected.
+	#[pallet::storage]
+	#[pallet::getter(fn rejected_documents)]
+	pub type RejectedDocuments<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, T::Hash, T::BlockNumber, OptionQuery>;
+
+	/// Number of fee-free votes cast by a committee member, along with the block they were cast
+	/// in.
+	#[pallet::storage]
+	#[pallet::getter(fn free_votes)]
+	pub type FreeVotes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32), OptionQuery>;
 
 	/// Stores the proposal propose by any members.
 	#[pallet::storage]
 	#[pallet::getter(fn proposal)]
-	pub type Proposal<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::Hash, ProposalInfo<BalanceIn<T>>, OptionQuery>;
+	pub type Proposal<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, ProposalInfoOf<T, I>, OptionQuery>;
 
 	/// List of Approver's which approve any proposal. Only Committee members are allowed to
 	/// approve.
 	#[pallet::storage]
 	#[pallet::getter(fn approvers)]
-	pub type Approvers<T: Config> =
+	pub type Approvers<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, T::Hash, Vec<T::AccountId>, ValueQuery>;
 
+	/// Community members standing in the next committee election, sorted by account, along with
+	/// the bond reserved from them.
+	#[pallet::storage]
+	#[pallet::getter(fn candidates)]
+	pub type Candidates<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Vec<(T::AccountId, BalanceIn<T, I>)>, ValueQuery>;
+
+	/// Candidates approved by each community member for the next committee election.
+	#[pallet::storage]
+	#[pallet::getter(fn election_votes)]
+	pub type ElectionVotes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<T::AccountId>, ValueQuery>;
+
+	/// Pending membership applications, keyed by the applicant.
+	#[pallet::storage]
+	#[pallet::getter(fn applications)]
+	pub type Applications<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Application<T::AccountId, BalanceIn<T, I>, T::Hash>,
+		OptionQuery,
+	>;
+
+	/// Calls dispatched once the proposal carrying them is approved.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_call)]
+	pub type ProposalCalls<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, <T as Config<I>>::RuntimeCall, OptionQuery>;
+
+	/// Dispatch weight of the call of a proposal, charged to whoever closes it.
+	#[pallet::storage]
+	#[pallet::getter(fn call_weight)]
+	pub type CallWeights<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, Weight, OptionQuery>;
+
+	/// Result of dispatching the call of an approved proposal.
+	#[pallet::storage]
+	#[pallet::getter(fn execution_result)]
+	pub type ExecutionResults<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::Hash, DispatchResult, OptionQuery>;
+
 	/// Account from where funds will be transfer.
 	#[pallet::storage]
 	#[pallet::getter(fn pot_account)]
-	pub type PotAccount<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+	pub type PotAccount<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	/// Donations made to the pot by each donor, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn donations)]
+	pub type Donations<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<DonationOf<T, I>>, ValueQuery>;
+
+	/// Total amount donated to the pot by each donor.
+	#[pallet::storage]
+	#[pallet::getter(fn donated)]
+	pub type DonorTotals<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceIn<T, I>, ValueQuery>;
+
+	/// Funds escrowed for a proposal by each donor, reserved until the proposal is paid.
+	#[pallet::storage]
+	#[pallet::getter(fn escrows)]
+	pub type Escrows<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceIn<T, I>,
+		ValueQuery,
+	>;
+
+	/// Number of funding rounds opened so far, the index of the next round.
+	#[pallet::storage]
+	#[pallet::getter(fn round_count)]
+	pub type RoundCount<T: Config<I>, I: 'static = ()> = StorageValue<_, RoundIndex, ValueQuery>;
+
+	/// Funding rounds which are not closed yet.
+	#[pallet::storage]
+	#[pallet::getter(fn round)]
+	pub type Rounds<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, RoundIndex, FundingRoundOf<T, I>, OptionQuery>;
+
+	/// Contributions made during a funding round by proposal and contributor, reserved until the
+	/// round is closed.
+	#[pallet::storage]
+	#[pallet::getter(fn contributions)]
+	pub type Contributions<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		RoundIndex,
+		Blake2_128Concat,
+		(T::Hash, T::AccountId),
+		BalanceIn<T, I>,
+		ValueQuery,
+	>;
+
+	/// Indices of the approved proposals ranked by the `RankingOrigin`, best first.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_ranking)]
+	pub type ProposalRanking<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, Vec<ProposalIndex>, ValueQuery>;
 
 	/// Pallets use events to inform users when important changes are made.
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
+	pub enum Event<T: Config<I>, I: 'static = ()> {
 		MemberAdded,
 		MemberAddedToCommittee,
 		ProposalReject,
 		ProposalApproved,
-		ProposalAdded,
+		ProposalAdded {
+			proposal_index: ProposalIndex,
+			proposal_hash: T::Hash,
+			document: T::Hash,
+		},
 		FundTransfer,
 		FundTransferDeclined,
 		Approved {
@@ -131,87 +909,355 @@ pub mod pallet {
 			voted: Vote,
 			ayes: MemberCount,
 			nays: MemberCount,
+			abstains: MemberCount,
 		},
-	}
-
-	/// Errors inform users that something went wrong.
-	#[pallet::error]
-	pub enum Error<T> {
-		/// If a member try to add in a community for multiple times.
-		AlreadyMemberOfCommunity,
-		/// If sudo try to add a community member in a committee multiple times.
-		AlreadyMemberOfCommittee,
-		/// If sudo try to add a member which is not a part of community.
-		MemberIsNotPresentInCommunity,
-		/// If a member try to add the same proposal multiple time.
-		ProposalAlreadyExist,
-		/// If a member try to approve the proposal and not a member committee.
-		MemberIsNotPresentInCommittee,
-		/// If a committee member try to approve a same proposal multiple times.
-		AlreadyApproved,
-		/// If a member try to approve the wrong proposal.
-		ProposalMissing,
-	}
-
-	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
-			let transaction_time = TransferTime::<T>::contains_key(n);
-			// Time after which action will take place according to votes.
-			if transaction_time {
-				let proposal_id = TransferTime::<T>::get(n);
-				let _result = Pallet::<T>::transfer_funds(proposal_id);
-			}
-			Weight::zero()
-		}
-	}
-
-	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
-	// These functions materialize as "extrinsics", which are often compared to transactions.
-	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
-		/// Anyone can join in the community member's list.
-		#[pallet::weight(10_000)]
-		pub fn add_community_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			ensure_signed(origin.clone())?;
-
-			// fetch the existing members from the community list.
-			let mut members = CommunityMembers::<T>::get();
-			// Search the new member in the existing member's list.
-			let location =
-				members.binary_search(&who).err().ok_or(Error::<T>::AlreadyMemberOfCommunity)?;
-
-			// add the new member in the community member's list.
-			members.insert(location, who.clone());
-
-			CommunityMembers::<T>::put(&members);
-
-			Self::deposit_event(Event::MemberAdded);
-			Ok(())
-		}
-
-		/// Add member's from community from committee list
-		/// Only Sudo can perform this action.
-		#[pallet::weight(10_000)]
-		pub fn add_committee_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			// Check origin is root.
-			ensure_root(origin.clone())?;
-
-			// member should be present in community members list
-			let community_member = CommunityMembers::<T>::get();
-			let _is_present = community_member
-				.binary_search(&who)
-				.map_err(|_| Error::<T>::MemberIsNotPresentInCommunity)?;
+		CandidateSubmitted {
+			candidate: T::AccountId,
+		},
+		CandidacyRenounced {
+			candidate: T::AccountId,
+		},
+		ElectionVoted {
+			voter: T::AccountId,
+			votes: MemberCount,
+		},
+		/// A new committee was elected and replaced the previous one.
+		NewTerm {
+			members: Vec<T::AccountId>,
+		},
+		/// No candidate received an approval, the previous committee stays in office.
+		EmptyTerm,
+		ApplicationSubmitted {
+			applicant: T::AccountId,
+			metadata: T::Hash,
+		},
+		Vouched {
+			voucher: T::AccountId,
+			applicant: T::AccountId,
+			vouches: MemberCount,
+		},
+		ApplicationApproved {
+			applicant: T::AccountId,
+		},
+		ApplicationRejected {
+			applicant: T::AccountId,
+			slashed: bool,
+		},
+		/// A committee member left the electorate of a proposal.
+		Recused {
+			member: T::AccountId,
+			proposal_hash: T::Hash,
+		},
+		VoteCommitted {
+			account: T::AccountId,
+			proposal_hash: T::Hash,
+		},
+		VoteRevealed {
+			account: T::AccountId,
+			proposal_hash: T::Hash,
+		},
+		/// The votes on a proposal were counted, unrevealed secret votes count as abstentions.
+		VotesTallied {
+			proposal_hash: T::Hash,
+			ayes: MemberCount,
+			nays: MemberCount,
+			abstains: MemberCount,
+		},
+		/// The proposer changed the terms of an open proposal.
+		ProposalAmended {
+			proposal_hash: T::Hash,
+			version: u32,
+			diff: AmendmentDiff<BalanceIn<T, I>, T::Hash>,
+		},
+		/// A settled proposal was removed from storage.
+		ProposalPruned {
+			proposal_index: ProposalIndex,
+			proposal_hash: T::Hash,
+		},
+		/// A community member donated to the pot.
+		Donated {
+			donor: T::AccountId,
+			amount: BalanceIn<T, I>,
+			memo: Option<T::Hash>,
+			/// Total donated by the donor so far.
+			total: BalanceIn<T, I>,
+		},
+		/// A donation to a proposal was escrowed.
+		DonatedToProposal {
+			proposal_hash: T::Hash,
+			donor: T::AccountId,
+			amount: BalanceIn<T, I>,
+			/// Total escrowed for the proposal.
+			escrowed: BalanceIn<T, I>,
+		},
+		/// The funds escrowed for a proposal were applied to its payout.
+		EscrowApplied {
+			proposal_hash: T::Hash,
+			amount: BalanceIn<T, I>,
+		},
+		/// The funds escrowed by a donor were refunded as the proposal wasn't approved.
+		EscrowRefunded {
+			proposal_hash: T::Hash,
+			donor: T::AccountId,
+			amount: BalanceIn<T, I>,
+		},
+		/// A funding round was opened.
+		RoundOpened {
+			round: RoundIndex,
+			start: T::BlockNumber,
+			end: T::BlockNumber,
+			matching_pool: BalanceIn<T, I>,
+		},
+		/// A community member contributed to a proposal during a funding round.
+		Contributed {
+			round: RoundIndex,
+			proposal_hash: T::Hash,
+			contributor: T::AccountId,
+			amount: BalanceIn<T, I>,
+		},
+		/// A proposal was paid its contributions and its share of the matching pool when the
+		/// funding round closed.
+		RoundMatched {
+			round: RoundIndex,
+			proposal_hash: T::Hash,
+			contributors: u32,
+			contributed: BalanceIn<T, I>,
+			matched: BalanceIn<T, I>,
+		},
+		/// A proposal couldn't be paid when the funding round closed, its contributions were
+		/// refunded.
+		RoundPayoutFailed {
+			round: RoundIndex,
+			proposal_hash: T::Hash,
+			error: DispatchError,
+		},
+		/// A funding round was closed, `matched` was paid out of its matching pool.
+		RoundClosed {
+			round: RoundIndex,
+			matched: BalanceIn<T, I>,
+		},
+		/// The approved proposals were ranked for the budget rounds.
+		ProposalsRanked {
+			ranking: Vec<ProposalIndex>,
+		},
+		/// An approved proposal didn't fit in the budget of the round, it rolls over.
+		ProposalDeferred {
+			proposal_hash: T::Hash,
+		},
+		/// A budget round paid `funded` proposals and deferred the others.
+		BudgetRoundClosed {
+			budget: BalanceIn<T, I>,
+			spent: BalanceIn<T, I>,
+			funded: u32,
+			deferred: u32,
+		},
+		/// The proposal was settled through `close`.
+		ProposalClosed {
+			proposal_hash: T::Hash,
+			status: ProposalStatus,
+		},
+		/// Too few committee members voted for the proposal to pass.
+		QuorumNotReached {
+			proposal_hash: T::Hash,
+			turnout: MemberCount,
+			required: MemberCount,
+		},
+		/// The call of an approved proposal was dispatched.
+		ProposalExecuted {
+			proposal_hash: T::Hash,
+			result: DispatchResult,
+		},
+		/// A proposal was approved but couldn't be paid, its payout can be retried with `close`.
+		PayoutFailed {
+			proposal_hash: T::Hash,
+			error: DispatchError,
+		},
+	}
 
-			let mut members = CommitteeMembers::<T>::get();
-			let location =
-				members.binary_search(&who).err().ok_or(Error::<T>::AlreadyMemberOfCommittee)?;
+	/// Errors inform users that something went wrong.
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// If a member try to add in a community for multiple times.
+		AlreadyMemberOfCommunity,
+		/// If sudo try to add a community member in a committee multiple times.
+		AlreadyMemberOfCommittee,
+		/// If sudo try to add a member which is not a part of community.
+		MemberIsNotPresentInCommunity,
+		/// If a member try to approve the proposal and not a member committee.
+		MemberIsNotPresentInCommittee,
+		/// If a committee member try to approve a same proposal multiple times.
+		AlreadyApproved,
+		/// If a member try to approve the wrong proposal.
+		ProposalMissing,
+		/// If a community member try to stand for the committee election multiple times.
+		AlreadyCandidate,
+		/// If a member try to renounce or vote for an account which is not a candidate.
+		NotACandidate,
+		/// If a member try to vote in the committee election without approving anyone.
+		NoVotes,
+		/// If an account try to apply for membership while an application is pending.
+		AlreadyApplied,
+		/// If a member try to vouch for or decide on an application which doesn't exist.
+		ApplicationMissing,
+		/// If a member try to vouch for the same applicant multiple times.
+		AlreadyVouched,
+		/// If sudo try to add a committee member once the committee is elected.
+		CommitteeElected,
+		/// If an account try to change or elect the committee while it is kept outside this
+		/// pallet.
+		ExternalCommittee,
+		/// If a member try to propose a call which is longer than `MaxCallLength`.
+		CallTooLong,
+		/// If the ranking origin ranks more proposals than `MaxRoundProposals`.
+		TooManyRanked,
+		/// If the ranking origin ranks a proposal twice, or one which is not approved or
+		/// deferred.
+		InvalidRanking,
+		/// If an account try to close a call proposal with a weight bound below its call weight.
+		WrongCallWeightBound,
+		/// If a member try to vote on or settle a proposal which is already settled.
+		ProposalNotOpen,
+		/// If a member try to propose with more payees than `MaxPayees`.
+		TooManyPayees,
+		/// If the payee's shares don't add up to 100% or an account is listed multiple times.
+		InvalidPayeeShares,
+		/// If a member try to close a proposal before its deadline while the outcome can change.
+		VotingStillOpen,
+		/// If the proposer or a payee of a proposal try to vote on it.
+		ConflictOfInterest,
+		/// If a committee member try to vote on or recuse from a proposal after recusing.
+		AlreadyRecused,
+		/// If a committee member try to vote publicly while secret ballots are used.
+		SecretBallotsActive,
+		/// If a committee member try to commit a vote while secret ballots aren't used.
+		SecretBallotsDisabled,
+		/// If a committee member try to commit a vote on the same proposal multiple times.
+		AlreadyCommitted,
+		/// If a committee member try to reveal a vote which wasn't committed.
+		CommitmentMissing,
+		/// If a committee member try to commit a vote after the commit phase.
+		NotCommitPhase,
+		/// If a committee member try to reveal a vote outside of the reveal phase.
+		NotRevealPhase,
+		/// If the revealed vote and salt don't match the commitment.
+		InvalidReveal,
+		/// If an account other than the proposer try to amend a proposal.
+		NotProposer,
+		/// If the proposer try to amend a proposal without changing any of its terms.
+		NoChanges,
+		/// If a member try to submit a proposal while having too many open proposals.
+		TooManyOpenProposals,
+		/// If a member try to submit proposals too close to each other.
+		SubmissionTooSoon,
+		/// If a member try to resubmit the document of a recently rejected proposal.
+		ResubmissionCooldown,
+		/// If a member try to submit a proposal whose document wasn't noted as a preimage.
+		DocumentMissing,
+		/// If a member try to submit a proposal with a title longer than `MaxTitleLength`.
+		TitleTooLong,
+		/// If an account try to prune a proposal which isn't settled yet.
+		ProposalNotSettled,
+		/// If a member try to donate before the pot account is set.
+		PotMissing,
+		/// If a member try to donate more than the proposal still needs.
+		DonationExceedsGap,
+		/// If a proposer try to lower the amount below the funds escrowed for the proposal.
+		AmountBelowEscrow,
+		/// If a proposer try to add a payee who already voted on the proposal.
+		PayeeAlreadyVoted,
+		/// If a donor try to withdraw from a proposal which is already voted on or settled.
+		DonationLocked,
+		/// If a donor try to withdraw from a proposal they didn't donate to.
+		NoDonation,
+		/// If a member try to donate nothing to a proposal.
+		ZeroDonation,
+		/// If sudo try to open a funding round which doesn't end after its start and now.
+		InvalidRoundPeriod,
+		/// If an account try to act on a funding round which doesn't exist or is closed.
+		RoundMissing,
+		/// If a member try to contribute outside of the funding round.
+		RoundNotActive,
+		/// If an account try to close a funding round before its end.
+		RoundNotEnded,
+		/// If a member try to contribute to a proposal which doesn't request funds.
+		NotFundingProposal,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let mut weight = Weight::zero();
+			// Time after which action will take place according to votes.
+			let due: Vec<ProposalId<T>> = TransferTime::<T, I>::drain_prefix(n)
+				.map(|(proposal_id, _)| proposal_id)
+				.collect();
+			for proposal_id in due {
+				// Calls are not dispatched here, they wait for someone to close them.
+				let _result = Pallet::<T, I>::transfer_funds(proposal_id, Weight::zero());
+				weight = weight.saturating_add(
+					T::DbWeight::get()
+						.writes(1)
+						.saturating_add(Pallet::<T, I>::settle_weight(T::MaxPayees::get())),
+				);
+			}
+			// Count the committee election at the end of every term.
+			let term = T::TermDuration::get();
+			if term != 0 && !T::CommitteeIsExternal::get() && (n % term.into()).is_zero() {
+				weight = weight.saturating_add(Pallet::<T, I>::elect_committee());
+			}
+			// Pay the approved proposals at the end of every budget period.
+			let budget_period = T::BudgetPeriod::get();
+			if budget_period != 0 && (n % budget_period.into()).is_zero() {
+				weight = weight.saturating_add(Pallet::<T, I>::close_budget_round());
+			}
+			weight
+		}
+	}
+
+	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
+	// These functions materialize as "extrinsics", which are often compared to transactions.
+	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Add an account to the community member's list.
+		/// Only the admission origin can perform this action, anyone else should apply.
+		#[pallet::weight(10_000)]
+		pub fn add_community_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AdmissionOrigin::ensure_origin(origin)?;
+			Self::insert_community_member(who)
+		}
+
+		/// Add member's from community from committee list
+		/// Only Sudo can perform this action, until the first committee election.
+		#[pallet::weight(10_000)]
+		pub fn add_committee_member(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			// Check origin is root.
+			ensure_root(origin.clone())?;
+			ensure!(!T::CommitteeIsExternal::get(), Error::<T, I>::ExternalCommittee);
+
+			// Sudo only bootstraps the committee, the elections replace it at every term.
+			let term = T::TermDuration::get();
+			ensure!(
+				term == 0 || frame_system::Pallet::<T>::block_number() < term.into(),
+				Error::<T, I>::CommitteeElected
+			);
+
+			// member should be present in community members list
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+
+			let mut members = CommitteeMembers::<T, I>::get();
+			let location = members
+				.binary_search(&who)
+				.err()
+				.ok_or(Error::<T, I>::AlreadyMemberOfCommittee)?;
 
 			members.insert(location, who.clone());
 
 			// Add member into the committee member's list
-			CommitteeMembers::<T>::put(&members);
+			CommitteeMembers::<T, I>::put(&members);
 
 			Self::deposit_event(Event::MemberAddedToCommittee);
 			Ok(())
@@ -219,103 +1265,365 @@ pub mod pallet {
 
 		/// Propose the Proposal to take funds
 		/// Anyone from community member's can propose.
+		/// The funds are split between the payees according to their shares, which should add up
+		/// to 100%. Without payees the proposer receives the whole amount.
 		#[pallet::weight(10_000_000)]
 		pub fn add_proposal(
 			origin: OriginFor<T>,
 			title: Vec<u8>,
-			proposal_hash: T::Hash,
-			amount: BalanceIn<T>,
+			document: T::Hash,
+			amount: BalanceIn<T, I>,
+			payees: Vec<(T::AccountId, Perbill)>,
 		) -> DispatchResult {
 			// Origin should be signed.
 			let who = ensure_signed(origin.clone())?;
 
-			// member should be present in community members list
-			let community_member = CommunityMembers::<T>::get();
-			let _is_present = community_member
-				.binary_search(&who)
-				.map_err(|_| Error::<T>::MemberIsNotPresentInCommunity)?;
-
-			ensure!(!Voting::<T>::contains_key(&proposal_hash), Error::<T>::ProposalAlreadyExist);
-
-			let info = { ProposalInfo { amount, title } };
-			// Add Proposal
-			<Proposal<T>>::insert(proposal_hash, info);
+			Self::insert_proposal(who, title, document, amount, payees)?;
+			Ok(())
+		}
 
-			// initially the votes will be null for any proposal.
-			let votes = { Votes { ayes: vec![], nays: vec![] } };
-			<Voting<T>>::insert(proposal_hash, votes);
+		/// Propose a call to be dispatched once the committee approves it.
+		/// Anyone from community member's can propose.
+		#[pallet::weight(10_000_000)]
+		pub fn add_call_proposal(
+			origin: OriginFor<T>,
+			title: Vec<u8>,
+			document: T::Hash,
+			call: Box<<T as Config<I>>::RuntimeCall>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			let mut user = Vec::new();
-			user.push(&who);
+			ensure!(
+				call.encoded_size() <= T::MaxCallLength::get() as usize,
+				Error::<T, I>::CallTooLong
+			);
 
-			<FundSeekerAccounts<T>>::insert(proposal_hash, user.clone());
-			Self::deposit_event(Event::ProposalAdded);
+			let proposal_hash =
+				Self::insert_proposal(who, title, document, Zero::zero(), Vec::new())?;
+			CallWeights::<T, I>::insert(proposal_hash, call.get_dispatch_info().weight);
+			ProposalCalls::<T, I>::insert(proposal_hash, *call);
 			Ok(())
 		}
 
 		/// Approve the Proposal propose by any community member
 		/// Only committee can propose the proposal
+		/// Valid votes are free, up to `MaxFreeVotes` per committee member in a block.
 		#[pallet::weight(10_000_000)]
 		pub fn approve_proposal(
 			origin: OriginFor<T>,
-			proposal_hash: T::Hash,
+			proposal: ProposalRef<T::Hash>,
 			approve: Vote,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+			// With secret ballots the votes are committed and revealed instead.
+			ensure!(!T::SecretBallots::get(), Error::<T, I>::SecretBallotsActive);
+
+			Self::ensure_can_vote(&who, &proposal_hash)?;
+			let tally = Self::record_vote(&who, &proposal_hash, approve.clone())?;
+
+			// Time after which the decision will make whether funds will be transfer or not,
+			// counted from the first vote.
+			if !VotingDeadline::<T, I>::contains_key(&proposal_hash) {
+				let expire_time = T::TimeDuration::get();
+				// Record the current BlockNumber and set the target BlockNumber.
+				let transaction_blocknumber =
+					frame_system::Pallet::<T>::block_number() + expire_time.into();
+				Self::schedule_settlement(&proposal_hash, transaction_blocknumber);
+			}
+
+			Self::deposit_event(Event::Approved {
+				account: who,
+				proposal_hash,
+				voted: approve,
+				ayes: tally.ayes,
+				nays: tally.nays,
+				abstains: tally.abstains,
+			});
+
+			let pays_fee = if Self::use_free_vote(&who) { Pays::No } else { Pays::Yes };
+			Ok(pays_fee.into())
+		}
+
+		/// Commit a secret vote during the commit phase of a proposal.
+		/// The commitment is the hash of the voter, the proposal hash, the vote and a salt,
+		/// `(AccountId, Hash, Vote, [u8; 32])`, so that it can't be copied by another member or
+		/// replayed on another proposal. The deposit is returned on reveal and slashed if the vote
+		/// is never revealed.
+		#[pallet::weight(10_000_000)]
+		pub fn commit_vote(
+			origin: OriginFor<T>,
+			proposal: ProposalRef<T::Hash>,
+			commitment: T::Hash,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+			ensure!(T::SecretBallots::get(), Error::<T, I>::SecretBallotsDisabled);
 
-			let committee_members = CommitteeMembers::<T>::get();
-			let _is_present = committee_members
-				.binary_search(&who)
-				.map_err(|_| Error::<T>::MemberIsNotPresentInCommittee)?;
+			Self::ensure_can_vote(&who, &proposal_hash)?;
+			ensure!(
+				!Commitments::<T, I>::contains_key(&proposal_hash, &who),
+				Error::<T, I>::AlreadyCommitted
+			);
+			let reveal_start =
+				Self::reveal_start(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() < reveal_start,
+				Error::<T, I>::NotCommitPhase
+			);
 
-			let total_approvers = Approvers::<T>::get(&proposal_hash);
+			let deposit = T::CommitDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			Commitments::<T, I>::insert(&proposal_hash, &who, (commitment, deposit));
 
-			let location =
-				total_approvers.binary_search(&who).err().ok_or(Error::<T>::AlreadyApproved)?;
+			Self::deposit_event(Event::VoteCommitted { account: who, proposal_hash });
+			Ok(())
+		}
+
+		/// Reveal a vote committed during the commit phase of a proposal.
+		/// The tally is only published once the proposal is settled.
+		#[pallet::weight(10_000_000)]
+		pub fn reveal_vote(
+			origin: OriginFor<T>,
+			proposal: ProposalRef<T::Hash>,
+			vote: Vote,
+			salt: [u8; 32],
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+
+			let (commitment, deposit) = Commitments::<T, I>::get(&proposal_hash, &who)
+				.ok_or(Error::<T, I>::CommitmentMissing)?;
+			let reveal_start =
+				Self::reveal_start(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			let deadline =
+				Self::voting_deadline(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(reveal_start <= now && now < deadline, Error::<T, I>::NotRevealPhase);
+			ensure!(
+				T::Hashing::hash_of(&(&who, &proposal_hash, &vote, salt)) == commitment,
+				Error::<T, I>::InvalidReveal
+			);
+
+			Commitments::<T, I>::remove(&proposal_hash, &who);
+			T::Currency::unreserve(&who, deposit);
+			Self::record_vote(&who, &proposal_hash, vote)?;
+
+			Self::deposit_event(Event::VoteRevealed { account: who, proposal_hash });
+			Ok(())
+		}
+
+		/// Change the amount, the payees or the document of an open proposal.
+		/// Only the proposer can amend a proposal, the previous terms are kept in the version
+		/// history and the votes are kept or reset according to the `AmendmentPolicy`. A new
+		/// document must be out of its resubmission cooldown, and kept votes can't come from a
+		/// new payee.
+		#[pallet::weight(10_000_000)]
+		pub fn amend_proposal(
+			origin: OriginFor<T>,
+			proposal: ProposalRef<T::Hash>,
+			amount: Option<BalanceIn<T, I>>,
+			payees: Option<Vec<(T::AccountId, Perbill)>>,
+			document: Option<T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+
+			let mut proposal_info =
+				Proposal::<T, I>::get(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			ensure!(proposal_info.proposer == who, Error::<T, I>::NotProposer);
+			ensure!(proposal_info.status == ProposalStatus::Open, Error::<T, I>::ProposalNotOpen);
 
-			// Check proposal is present or not.
-			let mut voting = Self::voting(&proposal_hash).ok_or(Error::<T>::ProposalMissing)?;
+			let old_payees = FundSeekerAccounts::<T, I>::get(&proposal_hash);
+			let amount = amount.filter(|amount| *amount != proposal_info.amount);
+			let payees = payees.filter(|payees| *payees != old_payees);
+			let document = document.filter(|document| *document != proposal_info.document);
+			ensure!(
+				amount.is_some() || payees.is_some() || document.is_some(),
+				Error::<T, I>::NoChanges
+			);
+			let votes_reset = T::AmendmentPolicy::get() == AmendmentPolicy::ResetVotes;
+			if let Some(payees) = &payees {
+				Self::ensure_valid_payees(payees)?;
+				// Kept votes can't come from a payee.
+				if !votes_reset {
+					let approvers = Approvers::<T, I>::get(&proposal_hash);
+					ensure!(
+						payees.iter().all(|(payee, _)| {
+							approvers.binary_search(payee).is_err() &&
+								!Commitments::<T, I>::contains_key(&proposal_hash, payee)
+						}),
+						Error::<T, I>::PayeeAlreadyVoted
+					);
+				}
+			}
+			if let Some(document) = &document {
+				ensure!(T::Preimages::have_preimage(document), Error::<T, I>::DocumentMissing);
+				Self::ensure_not_rejected(document)?;
+			}
+			if let Some(amount) = amount {
+				ensure!(amount >= proposal_info.escrowed, Error::<T, I>::AmountBelowEscrow);
+			}
 
-			// Cast vote on particular proposal
-			match approve {
-				// if recent member cast vote as 'aye'
-				Vote::Aye => {
-					voting.ayes.push(who.clone());
-					<Voting<T>>::insert(proposal_hash, voting.clone());
+			// Keep the current terms in the version history.
+			ProposalVersions::<T, I>::append(
+				&proposal_hash,
+				ProposalVersion {
+					version: proposal_info.version,
+					amount: proposal_info.amount,
+					payees: old_payees,
+					document: proposal_info.document,
+					amended_at: frame_system::Pallet::<T>::block_number(),
 				},
-				_ => {
-					voting.nays.push(who.clone());
-					<Voting<T>>::insert(proposal_hash, voting.clone());
+			);
+
+			let diff = AmendmentDiff {
+				amount: amount.map(|amount| (proposal_info.amount, amount)),
+				payees_changed: payees.is_some(),
+				document: document.map(|document| (proposal_info.document, document)),
+				votes_reset,
+			};
+
+			if let Some(payees) = payees {
+				Self::reindex_beneficiaries(proposal_info.index, &old_payees, &payees);
+				FundSeekerAccounts::<T, I>::insert(&proposal_hash, payees);
+			}
+			if let Some(amount) = amount {
+				proposal_info.amount = amount;
+				proposal_info.gap = amount.saturating_sub(proposal_info.escrowed);
+			}
+			if let Some(document) = document {
+				// Only the current document of a proposal is kept available.
+				T::Preimages::unrequest_preimage(&proposal_info.document);
+				T::Preimages::request_preimage(&document);
+				proposal_info.document = document;
+			}
+			proposal_info.version = proposal_info.version.saturating_add(1);
+			let version = proposal_info.version;
+			Proposal::<T, I>::insert(&proposal_hash, proposal_info);
+
+			if votes_reset {
+				Self::reset_votes(&proposal_hash);
+			}
+
+			Self::deposit_event(Event::ProposalAmended { proposal_hash, version, diff });
+			Ok(())
+		}
+
+		/// Leave the electorate of a proposal, e.g. on a conflict of interest.
+		/// Only a committee member who didn't vote yet can recuse, the quorum is then computed
+		/// against the reduced committee.
+		#[pallet::weight(10_000_000)]
+		pub fn recuse(origin: OriginFor<T>, proposal: ProposalRef<T::Hash>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+			Self::ensure_can_recuse(&who, &proposal_hash)?;
+
+			let mut recusals = Recusals::<T, I>::get(&proposal_hash);
+			let location =
+				recusals.binary_search(&who).err().ok_or(Error::<T, I>::AlreadyRecused)?;
+			recusals.insert(location, who.clone());
+			Recusals::<T, I>::insert(&proposal_hash, recusals);
+
+			Self::deposit_event(Event::Recused { member: who, proposal_hash });
+			Ok(())
+		}
+
+		/// Settle a proposal without waiting for the `TransferTime` hook.
+		/// Anyone can close a proposal once its deadline passed, or earlier when the remaining
+		/// votes can't change the outcome anymore. The weight of the unused payouts is refunded.
+		/// The call of an approved call proposal is only dispatched here, within
+		/// `call_weight_bound`.
+		#[pallet::weight(
+			Pallet::<T, I>::settle_weight(T::MaxPayees::get()).saturating_add(*call_weight_bound)
+		)]
+		pub fn close(
+			origin: OriginFor<T>,
+			proposal: ProposalRef<T::Hash>,
+			call_weight_bound: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+			if let Some(call_weight) = CallWeights::<T, I>::get(&proposal_hash) {
+				ensure!(
+					call_weight.all_lte(call_weight_bound),
+					Error::<T, I>::WrongCallWeightBound
+				);
+			}
+
+			let proposal_info =
+				Proposal::<T, I>::get(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			match proposal_info.status {
+				ProposalStatus::Open => {
+					let deadline_passed = Self::voting_deadline(&proposal_hash)
+						.map_or(false, |deadline| {
+							frame_system::Pallet::<T>::block_number() >= deadline
+						});
+					ensure!(
+						deadline_passed || Self::is_decided(&proposal_hash)?,
+						Error::<T, I>::VotingStillOpen
+					);
 				},
+				// The payout of an approved proposal can be retried at any time, unless it waits
+				// for a budget round. Approved calls wait for someone to close them.
+				ProposalStatus::Approved
+					if T::BudgetPeriod::get() == 0 ||
+						ProposalCalls::<T, I>::contains_key(&proposal_hash) => {},
+				_ => return Err(Error::<T, I>::ProposalNotOpen.into()),
 			}
-			// fetch all the approvers.
-			let mut members = Approvers::<T>::get(proposal_hash);
-			members.insert(location, who.clone());
-			// add new approver
-			Approvers::<T>::insert(proposal_hash, members);
 
-			// Time after which the decision will make whether funds will be transfer or not
-			let expire_time = T::TimeDuration::get();
-			// Record the current BlockNumber and set the target BlockNumber.
-			let transaction_blocknumber =
-				frame_system::Pallet::<T>::block_number() + expire_time.into();
-			TransferTime::<T>::insert(transaction_blocknumber, &proposal_hash);
+			let call_weight = Self::transfer_funds(proposal_hash, call_weight_bound)?;
+			Self::unschedule_settlement(&proposal_hash);
 
-			// fetch total no. of ayes and nays
-			let ayes_votes = voting.ayes.len() as MemberCount;
-			let nays_votes = voting.nays.len() as MemberCount;
+			let status = Proposal::<T, I>::get(&proposal_hash)
+				.map(|proposal_info| proposal_info.status)
+				.ok_or(Error::<T, I>::ProposalMissing)?;
+			Self::deposit_event(Event::ProposalClosed { proposal_hash, status });
 
-			Self::deposit_event(Event::Approved {
-				account: who,
-				proposal_hash,
-				voted: approve,
-				ayes: ayes_votes,
-				nays: nays_votes,
-			});
+			let payees = match status {
+				ProposalStatus::Paid => FundSeekerAccounts::<T, I>::decode_len(&proposal_hash)
+					.unwrap_or_default() as u32,
+				_ => 0,
+			};
+			Ok(Some(Self::settle_weight(payees).saturating_add(call_weight)).into())
+		}
+
+		/// Remove a settled proposal from storage, its document is unrequested.
+		/// Anyone can prune a rejected, paid or executed proposal.
+		#[pallet::weight(10_000_000)]
+		pub fn prune(origin: OriginFor<T>, proposal: ProposalRef<T::Hash>) -> DispatchResult {
+			ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+
+			let proposal_info =
+				Proposal::<T, I>::get(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			ensure!(
+				!matches!(
+					proposal_info.status,
+					ProposalStatus::Open | ProposalStatus::Approved | ProposalStatus::Deferred
+				),
+				Error::<T, I>::ProposalNotSettled
+			);
 
+			Self::remove_proposal(&proposal_hash, proposal_info);
 			Ok(())
 		}
 
+		/// Settle a proposal at the end of its voting period.
+		/// Dispatched by the scheduler when the pallet settles proposals through it.
+		#[pallet::weight(
+			Pallet::<T, I>::settle_weight(T::MaxPayees::get()).saturating_add(*call_weight_bound)
+		)]
+		pub fn settle(
+			origin: OriginFor<T>,
+			proposal_hash: T::Hash,
+			call_weight_bound: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let call_weight = Self::transfer_funds(proposal_hash, call_weight_bound)?;
+			Ok(Some(Self::settle_weight(T::MaxPayees::get()).saturating_add(call_weight)).into())
+		}
+
 		/// Set the Account from where the funds will be transferred.
 		/// Only sudo is allowed to make this happen.
 		#[pallet::weight(10_000_000)]
@@ -323,65 +1631,1450 @@ pub mod pallet {
 			ensure_root(origin.clone())?;
 			let mut accounts = Vec::new();
 			accounts.push(who.clone());
-			PotAccount::<T>::put(accounts);
+			PotAccount::<T, I>::put(accounts);
 			Ok(())
 		}
 
 		/// Any Community member can fund to the pot account.
+		/// The donation is recorded for the donor, along with the hash of an optional memo.
 		#[pallet::weight(10_000_000)]
 		pub fn fund_pot_account(
 			origin: OriginFor<T>,
-			who: T::AccountId,
-			amount: BalanceIn<T>,
+			amount: BalanceIn<T, I>,
+			memo: Option<T::Hash>,
 		) -> DispatchResult {
-			ensure_signed(origin.clone())?;
+			let who = ensure_signed(origin)?;
 			// member should be present in community members list
-			let community_member = CommunityMembers::<T>::get();
-			let _is_present = community_member
-				.binary_search(&who)
-				.map_err(|_| Error::<T>::MemberIsNotPresentInCommunity)?;
-			let accounts = PotAccount::<T>::get();
-			let pot_account = accounts[0].clone();
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+			let pot_account =
+				PotAccount::<T, I>::get().first().cloned().ok_or(Error::<T, I>::PotMissing)?;
 			T::Currency::transfer(&who, &pot_account, amount, ExistenceRequirement::KeepAlive)?;
+
+			let block = frame_system::Pallet::<T>::block_number();
+			Donations::<T, I>::append(&who, Donation { amount, memo, block });
+			let total = DonorTotals::<T, I>::mutate(&who, |total| {
+				*total = total.saturating_add(amount);
+				*total
+			});
+			Self::deposit_event(Event::Donated { donor: who, amount, memo, total });
 			Ok(())
 		}
-	}
-}
 
-impl<T: Config> Pallet<T> {
-	/// transfer the funds if the conditions are satisfied.
-	/// Funds will be transferred from pot account to the proposer account.
-	pub fn transfer_funds(proposal_id: T::Hash) -> DispatchResult {
-		// Check proposal is present
-		let voting = Self::voting(&proposal_id).ok_or(Error::<T>::ProposalMissing)?;
-
-		// fetch total no of ayes on a particular proposal
-		let no_of_ayes = voting.ayes.len() as MemberCount;
-		let no_of_committee_members = CommitteeMembers::<T>::get();
-		let proposal_info = Proposal::<T>::get(&proposal_id).ok_or(Error::<T>::ProposalMissing)?;
-		// Fetch the amount to transfer
-		let amount_to_transfer = proposal_info.amount;
-		// Fetch Proposer's account
-		let destination_accounts_list = FundSeekerAccounts::<T>::get(proposal_id);
-		let destination_account = destination_accounts_list[0].clone();
-		// Fetch pot accounts(source account)
-		let pot_accounts = PotAccount::<T>::get();
-		let source = pot_accounts[0].clone();
+		/// Any Community member can donate to an open or approved proposal, up to its gap.
+		/// The funds are reserved and applied before the pot's when the proposal is paid, they
+		/// are refunded if the proposal is rejected or its voting period ends without quorum.
+		/// Nothing expires the donations to a proposal nobody votes on, their donors take them
+		/// back with `withdraw_donation`.
+		#[pallet::weight(10_000_000)]
+		pub fn donate_to_proposal(
+			origin: OriginFor<T>,
+			proposal: ProposalRef<T::Hash>,
+			amount: BalanceIn<T, I>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// member should be present in community members list
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+			let proposal_hash = Self::resolve(proposal)?;
+			let mut proposal_info =
+				Proposal::<T, I>::get(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			ensure!(
+				matches!(
+					proposal_info.status,
+					ProposalStatus::Open | ProposalStatus::Approved | ProposalStatus::Deferred
+				),
+				Error::<T, I>::ProposalNotOpen
+			);
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroDonation);
+			ensure!(amount <= proposal_info.gap, Error::<T, I>::DonationExceedsGap);
 
-		// If all the committee members approve the proposal then only funds will be transferred.
-		if no_of_ayes == no_of_committee_members.len() as u32 {
-			T::Currency::transfer(
-				&source,
-				&destination_account,
-				amount_to_transfer,
-				ExistenceRequirement::KeepAlive,
-			)?;
-			Self::deposit_event(Event::FundTransfer);
-		} else {
-			// if condition is not satisfied.
-			Self::deposit_event(Event::FundTransferDeclined);
+			T::Currency::reserve(&who, amount)?;
+			Escrows::<T, I>::mutate(&proposal_hash, &who, |escrow| {
+				*escrow = escrow.saturating_add(amount)
+			});
+			proposal_info.escrowed = proposal_info.escrowed.saturating_add(amount);
+			proposal_info.gap = proposal_info.gap.saturating_sub(amount);
+			let escrowed = proposal_info.escrowed;
+			Proposal::<T, I>::insert(&proposal_hash, proposal_info);
+
+			Self::deposit_event(Event::DonatedToProposal {
+				proposal_hash,
+				donor: who,
+				amount,
+				escrowed,
+			});
+			Ok(())
 		}
 
-		Ok(())
+		/// Withdraw the donations escrowed for an open proposal nobody voted on yet.
+		/// Once its voting period started, the donations stay until the proposal is settled.
+		#[pallet::weight(10_000_000)]
+		pub fn withdraw_donation(
+			origin: OriginFor<T>,
+			proposal: ProposalRef<T::Hash>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let proposal_hash = Self::resolve(proposal)?;
+			let mut proposal_info =
+				Proposal::<T, I>::get(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			ensure!(
+				proposal_info.status == ProposalStatus::Open &&
+					!VotingDeadline::<T, I>::contains_key(&proposal_hash),
+				Error::<T, I>::DonationLocked
+			);
+
+			let amount = Escrows::<T, I>::take(&proposal_hash, &who);
+			ensure!(!amount.is_zero(), Error::<T, I>::NoDonation);
+			T::Currency::unreserve(&who, amount);
+			proposal_info.escrowed = proposal_info.escrowed.saturating_sub(amount);
+			proposal_info.gap = proposal_info.gap.saturating_add(amount);
+			Proposal::<T, I>::insert(&proposal_hash, proposal_info);
+
+			Self::deposit_event(Event::EscrowRefunded { proposal_hash, donor: who, amount });
+			Ok(())
+		}
+
+		/// Open a quadratic funding round, matching the contributions made from `start` until
+		/// `end` with up to `matching_pool` from the pot, which is reserved until the round closes.
+		/// Only sudo is allowed to open a round.
+		#[pallet::weight(10_000_000)]
+		pub fn open_round(
+			origin: OriginFor<T>,
+			start: T::BlockNumber,
+			end: T::BlockNumber,
+			matching_pool: BalanceIn<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				start < end && end > frame_system::Pallet::<T>::block_number(),
+				Error::<T, I>::InvalidRoundPeriod
+			);
+
+			let pot =
+				PotAccount::<T, I>::get().first().cloned().ok_or(Error::<T, I>::PotMissing)?;
+			T::Currency::reserve(&pot, matching_pool)?;
+
+			let round = RoundCount::<T, I>::get();
+			Rounds::<T, I>::insert(round, FundingRound { start, end, pot, matching_pool });
+			RoundCount::<T, I>::put(round.saturating_add(1));
+			Self::deposit_event(Event::RoundOpened { round, start, end, matching_pool });
+			Ok(())
+		}
+
+		/// Contribute to an open or approved proposal during a funding round.
+		/// Only community members can contribute, the funds are reserved until the round closes.
+		/// Contributions to a proposal which isn't approved or deferred by then are refunded.
+		#[pallet::weight(10_000_000)]
+		pub fn contribute(
+			origin: OriginFor<T>,
+			round: RoundIndex,
+			proposal: ProposalRef<T::Hash>,
+			amount: BalanceIn<T, I>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			// Only community members count, so that a contributor can't split into many accounts.
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+			let funding_round = Rounds::<T, I>::get(round).ok_or(Error::<T, I>::RoundMissing)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				funding_round.start <= now && now < funding_round.end,
+				Error::<T, I>::RoundNotActive
+			);
+
+			let proposal_hash = Self::resolve(proposal)?;
+			let proposal_info =
+				Proposal::<T, I>::get(&proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+			ensure!(
+				matches!(
+					proposal_info.status,
+					ProposalStatus::Open | ProposalStatus::Approved | ProposalStatus::Deferred
+				),
+				Error::<T, I>::ProposalNotOpen
+			);
+			ensure!(
+				!ProposalCalls::<T, I>::contains_key(&proposal_hash),
+				Error::<T, I>::NotFundingProposal
+			);
+
+			T::Currency::reserve(&who, amount)?;
+			Contributions::<T, I>::mutate(round, (proposal_hash, who.clone()), |contribution| {
+				*contribution = contribution.saturating_add(amount)
+			});
+			Self::deposit_event(Event::Contributed {
+				round,
+				proposal_hash,
+				contributor: who,
+				amount,
+			});
+			Ok(())
+		}
+
+		/// Close a funding round once it ended: each proposal is paid the contributions it
+		/// received and its share of the matching pool, in proportion to the square of the sum
+		/// of the square roots of its contributions.
+		/// Anyone can close a round. A proposal which can't be paid gets its contributions
+		/// refunded, without holding back the other proposals.
+		#[pallet::weight(10_000_000)]
+		pub fn close_round(origin: OriginFor<T>, round: RoundIndex) -> DispatchResult {
+			ensure_signed(origin)?;
+			let funding_round = Rounds::<T, I>::get(round).ok_or(Error::<T, I>::RoundMissing)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= funding_round.end,
+				Error::<T, I>::RoundNotEnded
+			);
+
+			T::Currency::unreserve(&funding_round.pot, funding_round.matching_pool);
+			let matched = Self::pay_round(round, &funding_round.pot, funding_round.matching_pool);
+			Rounds::<T, I>::remove(round);
+			Self::deposit_event(Event::RoundClosed { round, matched });
+			Ok(())
+		}
+
+		/// Rank the approved proposals for the budget rounds, best first. The proposals left out
+		/// of the ranking come after, by approval score.
+		/// Only the ranking origin is allowed to rank proposals.
+		#[pallet::weight(
+			T::DbWeight::get()
+				.reads(2u64.saturating_mul(ranking.len() as u64))
+				.saturating_add(Weight::from_ref_time(10_000_000))
+		)]
+		pub fn rank_proposals(origin: OriginFor<T>, ranking: Vec<ProposalIndex>) -> DispatchResult {
+			T::RankingOrigin::ensure_origin(origin)?;
+			ensure!(
+				ranking.len() <= T::MaxRoundProposals::get() as usize,
+				Error::<T, I>::TooManyRanked
+			);
+			let mut ranked = ranking.clone();
+			ranked.sort_unstable();
+			ranked.dedup();
+			ensure!(ranked.len() == ranking.len(), Error::<T, I>::InvalidRanking);
+			for index in ranking.iter() {
+				let status = ProposalHashes::<T, I>::get(index)
+					.and_then(|proposal_hash| Proposal::<T, I>::get(&proposal_hash))
+					.map(|proposal_info| proposal_info.status);
+				ensure!(
+					matches!(status, Some(ProposalStatus::Approved | ProposalStatus::Deferred)),
+					Error::<T, I>::InvalidRanking
+				);
+			}
+
+			ProposalRanking::<T, I>::put(&ranking);
+			Self::deposit_event(Event::ProposalsRanked { ranking });
+			Ok(())
+		}
+
+		/// Stand for a seat in the next committee election.
+		/// Any community member can be a candidate, the bond is returned once the election is
+		/// counted.
+		#[pallet::weight(10_000_000)]
+		pub fn submit_candidacy(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::CommitteeIsExternal::get(), Error::<T, I>::ExternalCommittee);
+
+			// member should be present in community members list
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+
+			let mut candidates = Candidates::<T, I>::get();
+			let location = candidates
+				.binary_search_by(|(candidate, _)| candidate.cmp(&who))
+				.err()
+				.ok_or(Error::<T, I>::AlreadyCandidate)?;
+
+			let bond = T::CandidacyBond::get();
+			T::Currency::reserve(&who, bond)?;
+			candidates.insert(location, (who.clone(), bond));
+			Candidates::<T, I>::put(&candidates);
+
+			Self::deposit_event(Event::CandidateSubmitted { candidate: who });
+			Ok(())
+		}
+
+		/// Withdraw from the next committee election and get the bond back.
+		#[pallet::weight(10_000_000)]
+		pub fn renounce_candidacy(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut candidates = Candidates::<T, I>::get();
+			let location = candidates
+				.binary_search_by(|(candidate, _)| candidate.cmp(&who))
+				.map_err(|_| Error::<T, I>::NotACandidate)?;
+
+			let (_, bond) = candidates.remove(location);
+			T::Currency::unreserve(&who, bond);
+			Candidates::<T, I>::put(&candidates);
+
+			Self::deposit_event(Event::CandidacyRenounced { candidate: who });
+			Ok(())
+		}
+
+		/// Approve a set of candidates for the next committee election.
+		/// Any community member can vote, a new call replaces the previous approvals.
+		#[pallet::weight(10_000_000)]
+		pub fn vote_candidates(
+			origin: OriginFor<T>,
+			mut votes: Vec<T::AccountId>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!T::CommitteeIsExternal::get(), Error::<T, I>::ExternalCommittee);
+
+			// member should be present in community members list
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+
+			votes.sort();
+			votes.dedup();
+			ensure!(!votes.is_empty(), Error::<T, I>::NoVotes);
+
+			// Every approved account should be standing in the election.
+			let candidates = Candidates::<T, I>::get();
+			for vote in votes.iter() {
+				candidates
+					.binary_search_by(|(candidate, _)| candidate.cmp(vote))
+					.map_err(|_| Error::<T, I>::NotACandidate)?;
+			}
+
+			let count = votes.len() as MemberCount;
+			ElectionVotes::<T, I>::insert(&who, votes);
+
+			Self::deposit_event(Event::ElectionVoted { voter: who, votes: count });
+			Ok(())
+		}
+
+		/// Apply to join the community member's list.
+		/// The deposit is returned on admission and refunded or slashed on rejection.
+		#[pallet::weight(10_000_000)]
+		pub fn apply_for_membership(origin: OriginFor<T>, metadata: T::Hash) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!T::CommunityProvider::contains(&who), Error::<T, I>::AlreadyMemberOfCommunity);
+			ensure!(!Applications::<T, I>::contains_key(&who), Error::<T, I>::AlreadyApplied);
+
+			let deposit = T::ApplicationDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			Applications::<T, I>::insert(&who, Application { deposit, metadata, vouchers: vec![] });
+
+			Self::deposit_event(Event::ApplicationSubmitted { applicant: who, metadata });
+			Ok(())
+		}
+
+		/// Vouch for a pending membership application.
+		/// The applicant is admitted once enough community members vouched for it.
+		#[pallet::weight(10_000_000)]
+		pub fn vouch(origin: OriginFor<T>, applicant: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// member should be present in community members list
+			ensure!(
+				T::CommunityProvider::contains(&who),
+				Error::<T, I>::MemberIsNotPresentInCommunity
+			);
+
+			let mut application =
+				Applications::<T, I>::get(&applicant).ok_or(Error::<T, I>::ApplicationMissing)?;
+			let location = application
+				.vouchers
+				.binary_search(&who)
+				.err()
+				.ok_or(Error::<T, I>::AlreadyVouched)?;
+			application.vouchers.insert(location, who.clone());
+
+			let vouches = application.vouchers.len() as MemberCount;
+			Applications::<T, I>::insert(&applicant, application);
+
+			Self::deposit_event(Event::Vouched {
+				voucher: who,
+				applicant: applicant.clone(),
+				vouches,
+			});
+
+			let threshold = T::VouchThreshold::get();
+			if threshold != 0 && vouches >= threshold {
+				Self::admit(applicant)?;
+			}
+			Ok(())
+		}
+
+		/// Admit a pending applicant without waiting for the vouch threshold.
+		/// Only the admission origin can perform this action.
+		#[pallet::weight(10_000_000)]
+		pub fn approve_application(
+			origin: OriginFor<T>,
+			applicant: T::AccountId,
+		) -> DispatchResult {
+			T::AdmissionOrigin::ensure_origin(origin)?;
+			Self::admit(applicant)
+		}
+
+		/// Reject a pending application, refunding or slashing the applicant's deposit.
+		/// Only the admission origin can perform this action.
+		#[pallet::weight(10_000_000)]
+		pub fn reject_application(
+			origin: OriginFor<T>,
+			applicant: T::AccountId,
+			slash: bool,
+		) -> DispatchResult {
+			T::AdmissionOrigin::ensure_origin(origin)?;
+
+			let application =
+				Applications::<T, I>::take(&applicant).ok_or(Error::<T, I>::ApplicationMissing)?;
+			if slash {
+				let _ = T::Currency::slash_reserved(&applicant, application.deposit);
+			} else {
+				T::Currency::unreserve(&applicant, application.deposit);
+			}
+
+			Self::deposit_event(Event::ApplicationRejected { applicant, slashed: slash });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// transfer the funds if the conditions are satisfied.
+	/// Funds will be transferred from pot account to the proposer account.
+	/// The call of a call proposal is dispatched when its weight fits in `call_weight_bound`,
+	/// the weight it used is returned.
+	pub fn transfer_funds(
+		proposal_id: T::Hash,
+		call_weight_bound: Weight,
+	) -> Result<Weight, DispatchError> {
+		let mut proposal_info =
+			Proposal::<T, I>::get(&proposal_id).ok_or(Error::<T, I>::ProposalMissing)?;
+		let settling = proposal_info.status == ProposalStatus::Open;
+		if settling {
+			Self::count_unrevealed(&proposal_id)?;
+		}
+
+		// Check proposal is present
+		let voting = Self::voting(&proposal_id).ok_or(Error::<T, I>::ProposalMissing)?;
+
+		// fetch total no of ayes, nays and abstentions on a particular proposal
+		let tally = voting.tally();
+		// Fetch the payee's accounts, the first one is the main beneficiary.
+		let payees = FundSeekerAccounts::<T, I>::get(proposal_id);
+		let destination_account = payees
+			.first()
+			.map(|(payee, _)| payee.clone())
+			.ok_or(Error::<T, I>::ProposalMissing)?;
+
+		if proposal_info.status == ProposalStatus::Open {
+			Self::deposit_event(Event::VotesTallied {
+				proposal_hash: proposal_id,
+				ayes: tally.ayes,
+				nays: tally.nays,
+				abstains: tally.abstains,
+			});
+			let electorate = Self::electorate(&proposal_id);
+			let status = Self::decide(&tally, electorate);
+			Self::set_status(
+				&proposal_id,
+				&mut proposal_info,
+				status,
+				&destination_account,
+				&tally,
+			);
+			match status {
+				ProposalStatus::Approved => Self::deposit_event(Event::ProposalApproved),
+				ProposalStatus::QuorumNotReached => {
+					Self::deposit_event(Event::QuorumNotReached {
+						proposal_hash: proposal_id,
+						turnout: tally.turnout(),
+						required: Self::required_turnout(electorate),
+					});
+					Self::deposit_event(Event::FundTransferDeclined);
+					return Ok(Weight::zero())
+				},
+				_ => {
+					// if condition is not satisfied.
+					Self::deposit_event(Event::ProposalReject);
+					Self::deposit_event(Event::FundTransferDeclined);
+					return Ok(Weight::zero())
+				},
+			}
+		}
+		// Only the payout of an approved proposal can be retried.
+		ensure!(proposal_info.status == ProposalStatus::Approved, Error::<T, I>::ProposalNotOpen);
+
+		// Call proposals dispatch their call instead of transferring funds.
+		if let Some(call_weight) = CallWeights::<T, I>::get(&proposal_id) {
+			if !call_weight.all_lte(call_weight_bound) {
+				return Ok(Weight::zero())
+			}
+			let call =
+				ProposalCalls::<T, I>::take(&proposal_id).ok_or(Error::<T, I>::ProposalMissing)?;
+			CallWeights::<T, I>::remove(&proposal_id);
+			let post_info = call.dispatch(RawOrigin::Proposal(proposal_id).into());
+			let actual_weight = match &post_info {
+				Ok(post_info) => post_info.actual_weight,
+				Err(e) => e.post_info.actual_weight,
+			}
+			.unwrap_or(call_weight);
+			let result = post_info.map(|_| ()).map_err(|e| e.error);
+			ExecutionResults::<T, I>::insert(proposal_id, result);
+			Self::set_status(
+				&proposal_id,
+				&mut proposal_info,
+				ProposalStatus::Executed,
+				&destination_account,
+				&tally,
+			);
+			Self::deposit_event(Event::ProposalExecuted { proposal_hash: proposal_id, result });
+			return Ok(actual_weight)
+		}
+
+		// With budget rounds, approved proposals wait for the next round to be paid.
+		if T::BudgetPeriod::get() != 0 {
+			return Ok(Weight::zero())
+		}
+		match Self::pay(&proposal_id, &mut proposal_info, &payees, &tally) {
+			// A proposal settling now keeps its outcome when its payout fails, whichever path
+			// settles it, and its payout is retried with `close`.
+			Err(error) if settling =>
+				Self::deposit_event(Event::PayoutFailed { proposal_hash: proposal_id, error }),
+			result => result?,
+		}
+		Ok(Weight::zero())
+	}
+
+	/// Pay an approved proposal out of the pot, after its escrowed donations.
+	/// The payout is all or nothing: when a payee can't be paid, e.g. below the existential
+	/// deposit, no payee is paid and the proposal stays approved.
+	fn pay(
+		proposal_hash: &T::Hash,
+		proposal_info: &mut ProposalInfoOf<T, I>,
+		payees: &[(T::AccountId, Perbill)],
+		tally: &Tally,
+	) -> DispatchResult {
+		let destination_account = payees
+			.first()
+			.map(|(payee, _)| payee.clone())
+			.ok_or(Error::<T, I>::ProposalMissing)?;
+		// Fetch pot accounts(source account)
+		let source = PotAccount::<T, I>::get().first().cloned().ok_or(Error::<T, I>::PotMissing)?;
+		// Either every payee is paid or none of them. The escrowed donations are moved to the
+		// pot first, so that the pot only pays the gap. What the funding rounds already paid is
+		// left out of the gap.
+		let due = proposal_info.escrowed.saturating_add(proposal_info.gap);
+		let escrowed = with_storage_layer(|| -> Result<BalanceIn<T, I>, DispatchError> {
+			let escrowed = Self::apply_escrow(proposal_hash, &source)?;
+			for (payee, amount) in Self::split_amount(payees, due) {
+				T::Currency::transfer(&source, &payee, amount, ExistenceRequirement::KeepAlive)?;
+			}
+			Ok(escrowed)
+		})?;
+		if !escrowed.is_zero() {
+			Self::deposit_event(Event::EscrowApplied {
+				proposal_hash: *proposal_hash,
+				amount: escrowed,
+			});
+		}
+		Self::set_status(
+			proposal_hash,
+			proposal_info,
+			ProposalStatus::Paid,
+			&destination_account,
+			tally,
+		);
+		Self::deposit_event(Event::FundTransfer);
+
+		Ok(())
+	}
+
+	/// Pay the approved and deferred proposals in rank order, within the balance the pot can
+	/// spend. The proposals which don't fit in this budget are deferred to the next round.
+	/// Only the first `MaxRoundProposals` proposals are considered, the ones ranked after are
+	/// deferred as well. Returns the weight used.
+	pub fn close_budget_round() -> Weight {
+		let mut candidates = Self::ranked_proposals();
+		// Ranking reads the proposal, its votes and its payees, then every proposal is either
+		// paid or deferred.
+		let weight = T::DbWeight::get().reads((candidates.len() as u64).saturating_mul(4));
+		if candidates.is_empty() {
+			return weight
+		}
+		let mut weight = weight.saturating_add(
+			Self::settle_weight(T::MaxPayees::get()).saturating_mul(candidates.len() as u64),
+		);
+		let left_out =
+			candidates.split_off(candidates.len().min(T::MaxRoundProposals::get() as usize));
+		let budget = match PotAccount::<T, I>::get().first() {
+			Some(pot) =>
+				T::Currency::free_balance(pot).saturating_sub(T::Currency::minimum_balance()),
+			None => Zero::zero(),
+		};
+		let costs: Vec<BalanceIn<T, I>> =
+			candidates.iter().map(|(_, proposal_info)| proposal_info.gap).collect();
+		let selected = match T::BudgetAllocation::get() {
+			BudgetAllocation::Greedy => Self::allocate_greedy(&costs, budget),
+			BudgetAllocation::Knapsack => {
+				weight = weight.saturating_add(Self::knapsack_weight(costs.len() as u32, budget));
+				Self::allocate_knapsack(&costs, budget)
+			},
+		};
+
+		let (mut spent, mut funded, mut deferred) = (BalanceIn::<T, I>::zero(), 0u32, 0u32);
+		let left_out = left_out.into_iter().map(|candidate| (candidate, false));
+		for ((proposal_hash, mut proposal_info), selected) in
+			candidates.into_iter().zip(selected).chain(left_out)
+		{
+			let payees = FundSeekerAccounts::<T, I>::get(&proposal_hash);
+			let tally =
+				Self::voting(&proposal_hash).map(|voting| voting.tally()).unwrap_or_default();
+			let cost = proposal_info.gap;
+			if selected && Self::pay(&proposal_hash, &mut proposal_info, &payees, &tally).is_ok() {
+				spent = spent.saturating_add(cost);
+				funded = funded.saturating_add(1);
+				continue
+			}
+			if proposal_info.status != ProposalStatus::Deferred {
+				if let Some((beneficiary, _)) = payees.first() {
+					Self::set_status(
+						&proposal_hash,
+						&mut proposal_info,
+						ProposalStatus::Deferred,
+						beneficiary,
+						&tally,
+					);
+				}
+			}
+			deferred = deferred.saturating_add(1);
+			Self::deposit_event(Event::ProposalDeferred { proposal_hash });
+		}
+		Self::deposit_event(Event::BudgetRoundClosed { budget, spent, funded, deferred });
+		weight
+	}
+
+	/// Weight of the knapsack over `proposals` costs. Each proposal explores at most every
+	/// amount reachable so far, which is bounded both by the `2^proposals` selections and by the
+	/// amounts of the budget, in the smallest unit of the currency.
+	pub fn knapsack_weight(proposals: u32, budget: BalanceIn<T, I>) -> Weight {
+		let selections = 1u128.checked_shl(proposals).unwrap_or(u128::MAX);
+		let amounts = budget.saturated_into::<u128>().saturating_add(1);
+		let steps = selections.min(amounts).saturating_mul(proposals.into());
+		Weight::from_ref_time(KNAPSACK_STEP_WEIGHT.saturating_mul(steps.saturated_into::<u64>()))
+	}
+
+	/// Approved and deferred proposals, by rank for the next budget round. Proposals ranked by
+	/// the `RankingOrigin` come first when the runtime uses its ranking, the others are ordered
+	/// by approval score and then by index.
+	pub fn ranked_proposals() -> Vec<(T::Hash, ProposalInfoOf<T, I>)> {
+		let ranking = match T::BudgetRanking::get() {
+			BudgetRanking::CommitteeRanking => ProposalRanking::<T, I>::get(),
+			BudgetRanking::ApprovalScore => Vec::new(),
+		};
+		let mut candidates: Vec<_> = [ProposalStatus::Approved, ProposalStatus::Deferred]
+			.into_iter()
+			.flat_map(ProposalsByStatus::<T, I>::get)
+			.filter_map(|index| {
+				let proposal_hash = ProposalHashes::<T, I>::get(index)?;
+				// Approved calls are dispatched when closed, not paid by the rounds.
+				if ProposalCalls::<T, I>::contains_key(&proposal_hash) {
+					return None
+				}
+				Some((proposal_hash, Proposal::<T, I>::get(&proposal_hash)?))
+			})
+			.collect();
+		candidates.sort_by_cached_key(|(proposal_hash, proposal_info)| {
+			let rank = ranking.iter().position(|index| *index == proposal_info.index);
+			(
+				rank.unwrap_or(usize::MAX),
+				Reverse(Self::approval_score(proposal_hash)),
+				proposal_info.index,
+			)
+		});
+		candidates
+	}
+
+	/// Share of the electorate which approved the proposal.
+	pub fn approval_score(proposal_hash: &T::Hash) -> Perbill {
+		let ayes = Self::voting(proposal_hash).map(|voting| voting.tally().ayes).unwrap_or(0);
+		Perbill::from_rational(ayes, Self::electorate(proposal_hash).max(1))
+	}
+
+	/// Select, in rank order, the costs which still fit in what is left of the budget.
+	pub fn allocate_greedy(costs: &[BalanceIn<T, I>], budget: BalanceIn<T, I>) -> Vec<bool> {
+		let mut left = budget;
+		costs
+			.iter()
+			.map(|cost| {
+				let fits = *cost <= left;
+				if fits {
+					left = left.saturating_sub(*cost);
+				}
+				fits
+			})
+			.collect()
+	}
+
+	/// Select the costs spending the most of the budget, whatever their rank. Between selections
+	/// spending the same amount, the one complete the earliest in rank order is kept. Every
+	/// amount which can be spent within the budget is explored, which is why the rounds are
+	/// bounded by `MaxRoundProposals`.
+	pub fn allocate_knapsack(costs: &[BalanceIn<T, I>], budget: BalanceIn<T, I>) -> Vec<bool> {
+		let costs: Vec<u128> = costs.iter().map(|cost| cost.saturated_into::<u128>()).collect();
+		let budget = budget.saturated_into::<u128>();
+
+		// Every amount which can be spent, along with the last cost selected to spend it and the
+		// amount spent before.
+		let mut reachable: BTreeMap<u128, Option<(usize, u128)>> = BTreeMap::new();
+		reachable.insert(0, None);
+		for (i, cost) in costs.iter().enumerate() {
+			if *cost == 0 || *cost > budget {
+				continue
+			}
+			let spent: Vec<u128> = reachable.keys().copied().collect();
+			for before in spent {
+				let after = before.saturating_add(*cost);
+				if after <= budget {
+					reachable.entry(after).or_insert(Some((i, before)));
+				}
+			}
+		}
+
+		// Free proposals are always selected.
+		let mut selection: Vec<bool> = costs.iter().map(|cost| *cost == 0).collect();
+		let mut spent = reachable.keys().next_back().copied().unwrap_or_default();
+		while let Some(Some((i, before))) = reachable.get(&spent) {
+			selection[*i] = true;
+			spent = *before;
+		}
+		selection
+	}
+
+	/// Whether the remaining votes can't change the outcome of an open proposal anymore.
+	pub fn is_decided(proposal_hash: &T::Hash) -> Result<bool, DispatchError> {
+		let voting = Self::voting(proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+		let tally = voting.tally();
+		let electorate = Self::electorate(proposal_hash);
+
+		// Secret votes which are still to be revealed could change the outcome.
+		if Commitments::<T, I>::iter_prefix(proposal_hash).next().is_some() {
+			return Ok(false)
+		}
+
+		// Once everyone voted nothing can change. Once the quorum is reached, the outcome is
+		// known when the remaining votes can't overturn the majority.
+		let remaining = electorate.saturating_sub(tally.turnout());
+		Ok(tally.turnout() >= electorate ||
+			(tally.turnout() >= Self::required_turnout(electorate) &&
+				(tally.ayes > tally.nays.saturating_add(remaining) ||
+					tally.nays >= tally.ayes.saturating_add(remaining))))
+	}
+
+	/// Check the committee member is allowed to vote on the proposal and didn't vote yet.
+	pub(crate) fn ensure_can_vote(
+		who: &T::AccountId,
+		proposal_hash: &T::Hash,
+	) -> Result<(), Error<T, I>> {
+		ensure!(T::CommitteeProvider::contains(who), Error::<T, I>::MemberIsNotPresentInCommittee);
+
+		let total_approvers = Approvers::<T, I>::get(proposal_hash);
+		ensure!(total_approvers.binary_search(who).is_err(), Error::<T, I>::AlreadyApproved);
+
+		// Check proposal is present or not.
+		let proposal_info =
+			Proposal::<T, I>::get(proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+		ensure!(proposal_info.status == ProposalStatus::Open, Error::<T, I>::ProposalNotOpen);
+		ensure!(
+			!Self::has_conflict(
+				&proposal_info,
+				&FundSeekerAccounts::<T, I>::get(proposal_hash),
+				who
+			),
+			Error::<T, I>::ConflictOfInterest
+		);
+		ensure!(
+			Recusals::<T, I>::get(proposal_hash).binary_search(who).is_err(),
+			Error::<T, I>::AlreadyRecused
+		);
+		Ok(())
+	}
+
+	/// Check the committee member is allowed to recuse from the proposal: it is still open and
+	/// the member neither voted on it nor recused already.
+	pub(crate) fn ensure_can_recuse(
+		who: &T::AccountId,
+		proposal_hash: &T::Hash,
+	) -> Result<(), Error<T, I>> {
+		ensure!(T::CommitteeProvider::contains(who), Error::<T, I>::MemberIsNotPresentInCommittee);
+
+		let proposal_info =
+			Proposal::<T, I>::get(proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+		ensure!(proposal_info.status == ProposalStatus::Open, Error::<T, I>::ProposalNotOpen);
+		ensure!(
+			Approvers::<T, I>::get(proposal_hash).binary_search(who).is_err() &&
+				!Commitments::<T, I>::contains_key(proposal_hash, who),
+			Error::<T, I>::AlreadyApproved
+		);
+		ensure!(
+			Recusals::<T, I>::get(proposal_hash).binary_search(who).is_err(),
+			Error::<T, I>::AlreadyRecused
+		);
+		Ok(())
+	}
+
+	/// Whether the committee member has fee-free votes left in the current block.
+	fn has_free_vote(who: &T::AccountId) -> bool {
+		match FreeVotes::<T, I>::get(who) {
+			Some((block, count)) if block == frame_system::Pallet::<T>::block_number() =>
+				count < T::MaxFreeVotes::get(),
+			_ => T::MaxFreeVotes::get() > 0,
+		}
+	}
+
+	/// Use one of the fee-free votes of a committee member, returns whether one was left.
+	fn use_free_vote(who: &T::AccountId) -> bool {
+		if !Self::has_free_vote(who) {
+			return false
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		FreeVotes::<T, I>::mutate(who, |free_votes| {
+			*free_votes = match free_votes.take() {
+				Some((block, count)) if block == now => Some((now, count.saturating_add(1))),
+				_ => Some((now, 1)),
+			};
+		});
+		true
+	}
+
+	/// Whether a vote of the account on the proposal would be free of fees.
+	pub fn is_free_vote(who: &T::AccountId, proposal: ProposalRef<T::Hash>) -> bool {
+		!T::SecretBallots::get() &&
+			Self::resolve(proposal)
+				.map_or(false, |hash| Self::ensure_can_vote(who, &hash).is_ok()) &&
+			Self::has_free_vote(who)
+	}
+
+	/// Cast the vote of a committee member and return the updated tally.
+	fn record_vote(
+		who: &T::AccountId,
+		proposal_hash: &T::Hash,
+		vote: Vote,
+	) -> Result<Tally, DispatchError> {
+		let mut voting = Self::voting(proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+
+		// fetch all the approvers.
+		let mut members = Approvers::<T, I>::get(proposal_hash);
+		let location = members.binary_search(who).err().ok_or(Error::<T, I>::AlreadyApproved)?;
+
+		// Cast vote on particular proposal
+		match vote {
+			// if recent member cast vote as 'aye'
+			Vote::Aye => voting.ayes.push(who.clone()),
+			Vote::Nay => voting.nays.push(who.clone()),
+			// if recent member abstained, e.g. on a conflict of interest
+			Vote::Abstain => voting.abstains.push(who.clone()),
+		}
+		<Voting<T, I>>::insert(proposal_hash, &voting);
+
+		// add new approver
+		members.insert(location, who.clone());
+		Approvers::<T, I>::insert(proposal_hash, members);
+
+		Ok(voting.tally())
+	}
+
+	/// Count the secret votes which were never revealed as abstentions and slash their deposit.
+	fn count_unrevealed(proposal_hash: &T::Hash) -> DispatchResult {
+		for (who, (_, deposit)) in Commitments::<T, I>::drain_prefix(proposal_hash) {
+			let _ = T::Currency::slash_reserved(&who, deposit);
+			Self::record_vote(&who, proposal_hash, Vote::Abstain)?;
+		}
+		Ok(())
+	}
+
+	/// Drop the votes cast on a proposal, committed secret votes get their deposit back.
+	/// Public votes start a new voting period with the next vote, secret ballots keep their
+	/// phases.
+	fn reset_votes(proposal_hash: &T::Hash) {
+		<Voting<T, I>>::insert(
+			proposal_hash,
+			Votes { ayes: vec![], nays: vec![], abstains: vec![] },
+		);
+		Approvers::<T, I>::remove(proposal_hash);
+		for (who, (_, deposit)) in Commitments::<T, I>::drain_prefix(proposal_hash) {
+			T::Currency::unreserve(&who, deposit);
+		}
+
+		if !T::SecretBallots::get() {
+			Self::unschedule_settlement(proposal_hash);
+			VotingDeadline::<T, I>::remove(proposal_hash);
+		}
+	}
+
+	/// Settle the proposal at the given block, unless it is closed earlier.
+	fn schedule_settlement(proposal_hash: &T::Hash, deadline: T::BlockNumber) {
+		if !T::Settlement::schedule(proposal_hash, deadline) {
+			TransferTime::<T, I>::insert(deadline, proposal_hash, ());
+		}
+		VotingDeadline::<T, I>::insert(proposal_hash, deadline);
+	}
+
+	/// Drop the scheduled settlement of the proposal.
+	fn unschedule_settlement(proposal_hash: &T::Hash) {
+		T::Settlement::cancel(proposal_hash);
+		if let Some(deadline) = VotingDeadline::<T, I>::get(proposal_hash) {
+			TransferTime::<T, I>::remove(deadline, proposal_hash);
+		}
+	}
+
+	/// Number of committee members allowed to vote on a proposal.
+	/// Members who recused themselves, the proposer and the payees are left out.
+	pub fn electorate(proposal_hash: &T::Hash) -> MemberCount {
+		let proposal_info = match Proposal::<T, I>::get(proposal_hash) {
+			Some(proposal_info) => proposal_info,
+			None => return 0,
+		};
+		let recusals = Recusals::<T, I>::get(proposal_hash);
+		let payees = FundSeekerAccounts::<T, I>::get(proposal_hash);
+
+		T::CommitteeProvider::sorted_members()
+			.iter()
+			.filter(|member| {
+				recusals.binary_search(member).is_err() &&
+					!Self::has_conflict(&proposal_info, &payees, member)
+			})
+			.count() as MemberCount
+	}
+
+	/// Whether the account proposed the proposal or receives a part of its funds.
+	fn has_conflict(
+		proposal_info: &ProposalInfoOf<T, I>,
+		payees: &[(T::AccountId, Perbill)],
+		who: &T::AccountId,
+	) -> bool {
+		proposal_info.proposer == *who || payees.iter().any(|(payee, _)| payee == who)
+	}
+
+	/// Weight of settling a proposal which pays out `payees` accounts.
+	pub fn settle_weight(payees: u32) -> Weight {
+		T::DbWeight::get()
+			.reads_writes(6, 4)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2).saturating_mul(payees.into()))
+			.saturating_add(Weight::from_ref_time(10_000_000))
+	}
+
+	/// Number of committee members who have to vote for a proposal to pass.
+	pub fn required_turnout(electorate: MemberCount) -> MemberCount {
+		T::Quorum::get().mul_ceil(electorate)
+	}
+
+	/// Outcome of the votes once the voting is over.
+	/// Once the quorum is reached, the proposal passes with a majority of ayes among the votes
+	/// which didn't abstain, members who didn't vote are left out.
+	pub fn decide(tally: &Tally, electorate: MemberCount) -> ProposalStatus {
+		if tally.turnout() < Self::required_turnout(electorate) {
+			ProposalStatus::QuorumNotReached
+		} else if tally.ayes > tally.nays {
+			ProposalStatus::Approved
+		} else {
+			ProposalStatus::Rejected
+		}
+	}
+
+	/// Split the amount between the payees according to their shares.
+	/// Shares are rounded down and the dust goes to the first payee, so the whole amount is paid.
+	pub fn split_amount(
+		payees: &[(T::AccountId, Perbill)],
+		amount: BalanceIn<T, I>,
+	) -> Vec<(T::AccountId, BalanceIn<T, I>)> {
+		let mut split: Vec<(T::AccountId, BalanceIn<T, I>)> = payees
+			.iter()
+			.map(|(payee, share)| (payee.clone(), share.mul_floor(amount)))
+			.collect();
+		let paid = split
+			.iter()
+			.fold(BalanceIn::<T, I>::zero(), |acc, (_, part)| acc.saturating_add(*part));
+		if let Some((_, part)) = split.first_mut() {
+			*part = part.saturating_add(amount.saturating_sub(paid));
+		}
+		split
+	}
+
+	/// Move the proposal to a new status and notify the outcome handlers.
+	fn set_status(
+		proposal_hash: &T::Hash,
+		proposal_info: &mut ProposalInfoOf<T, I>,
+		status: ProposalStatus,
+		beneficiary: &T::AccountId,
+		tally: &Tally,
+	) {
+		Self::remove_index::<ProposalsByStatus<T, I>, _>(proposal_info.status, proposal_info.index);
+		Self::insert_index::<ProposalsByStatus<T, I>, _>(status, proposal_info.index);
+		if proposal_info.status == ProposalStatus::Open {
+			OpenProposals::<T, I>::mutate_exists(&proposal_info.proposer, |open| {
+				*open = open.map(|open| open.saturating_sub(1)).filter(|open| *open > 0);
+			});
+		}
+		if status == ProposalStatus::Rejected {
+			RejectedDocuments::<T, I>::insert(
+				proposal_info.document,
+				frame_system::Pallet::<T>::block_number(),
+			);
+		}
+		if matches!(status, ProposalStatus::Rejected | ProposalStatus::QuorumNotReached) {
+			Self::refund_escrow(proposal_hash, proposal_info);
+		}
+		proposal_info.status = status;
+		Proposal::<T, I>::insert(proposal_hash, &*proposal_info);
+		T::OnProposalOutcome::on_proposal_outcome(
+			proposal_hash,
+			status,
+			beneficiary,
+			proposal_info.amount,
+			tally,
+		);
+	}
+
+	/// Move the funds escrowed for a proposal to the pot, returns the amount moved.
+	fn apply_escrow(
+		proposal_hash: &T::Hash,
+		pot: &T::AccountId,
+	) -> Result<BalanceIn<T, I>, DispatchError> {
+		let mut applied = BalanceIn::<T, I>::zero();
+		for (donor, amount) in Escrows::<T, I>::drain_prefix(proposal_hash) {
+			T::Currency::repatriate_reserved(&donor, pot, amount, BalanceStatus::Free)?;
+			applied = applied.saturating_add(amount);
+		}
+		Ok(applied)
+	}
+
+	/// Pay the contributions made during a funding round and the matching pool to the payees
+	/// of the proposals, returns the amount paid out of the matching pool.
+	/// Only approved and deferred proposals are funded, the contributions to the others are
+	/// refunded. A proposal is matched up to what it still misses, and paid once it misses
+	/// nothing anymore. When the payout of a proposal fails, its contributions are refunded.
+	fn pay_round(
+		round: RoundIndex,
+		pot: &T::AccountId,
+		matching_pool: BalanceIn<T, I>,
+	) -> BalanceIn<T, I> {
+		// Group the contributions by proposal, in a deterministic order.
+		let mut contributions: BTreeMap<T::Hash, Vec<(T::AccountId, BalanceIn<T, I>)>> =
+			BTreeMap::new();
+		for ((proposal_hash, contributor), amount) in Contributions::<T, I>::drain_prefix(round) {
+			let fundable = Proposal::<T, I>::get(&proposal_hash).map_or(false, |proposal_info| {
+				matches!(proposal_info.status, ProposalStatus::Approved | ProposalStatus::Deferred)
+			});
+			if fundable {
+				contributions.entry(proposal_hash).or_default().push((contributor, amount));
+			} else {
+				T::Currency::unreserve(&contributor, amount);
+			}
+		}
+
+		let roots: Vec<u128> = contributions
+			.values()
+			.map(|contributions| {
+				contributions
+					.iter()
+					.fold(0u128, |root, (_, amount)| root.saturating_add(Self::fixed_sqrt(*amount)))
+			})
+			.collect();
+		let matches = Self::quadratic_matches(&roots, matching_pool);
+
+		let mut matched = BalanceIn::<T, I>::zero();
+		for ((proposal_hash, contributions), matching) in contributions.into_iter().zip(matches) {
+			// Each proposal is paid in full or not at all.
+			let paid = with_storage_layer(|| -> Result<BalanceIn<T, I>, DispatchError> {
+				Self::pay_round_proposal(round, pot, &proposal_hash, &contributions, matching)
+			});
+			match paid {
+				Ok(matching) => matched = matched.saturating_add(matching),
+				Err(error) => {
+					for (contributor, amount) in contributions {
+						T::Currency::unreserve(&contributor, amount);
+					}
+					Self::deposit_event(Event::RoundPayoutFailed { round, proposal_hash, error });
+				},
+			}
+		}
+		matched
+	}
+
+	/// Pay a proposal its contributions and its match, returns the amount matched.
+	fn pay_round_proposal(
+		round: RoundIndex,
+		pot: &T::AccountId,
+		proposal_hash: &T::Hash,
+		contributions: &[(T::AccountId, BalanceIn<T, I>)],
+		matching: BalanceIn<T, I>,
+	) -> Result<BalanceIn<T, I>, DispatchError> {
+		let mut proposal_info =
+			Proposal::<T, I>::get(proposal_hash).ok_or(Error::<T, I>::ProposalMissing)?;
+		let mut contributed = BalanceIn::<T, I>::zero();
+		for (contributor, amount) in contributions.iter() {
+			T::Currency::repatriate_reserved(contributor, pot, *amount, BalanceStatus::Free)?;
+			contributed = contributed.saturating_add(*amount);
+		}
+		let matching = matching.min(proposal_info.gap.saturating_sub(contributed));
+		let payees = FundSeekerAccounts::<T, I>::get(proposal_hash);
+		let payout = contributed.saturating_add(matching);
+		for (payee, amount) in Self::split_amount(&payees, payout) {
+			T::Currency::transfer(pot, &payee, amount, ExistenceRequirement::KeepAlive)?;
+		}
+		proposal_info.gap = proposal_info.gap.saturating_sub(payout);
+		Self::deposit_event(Event::RoundMatched {
+			round,
+			proposal_hash: *proposal_hash,
+			contributors: contributions.len() as u32,
+			contributed,
+			matched: matching,
+		});
+
+		if proposal_info.gap.is_zero() {
+			let tally =
+				Self::voting(proposal_hash).map(|voting| voting.tally()).unwrap_or_default();
+			Self::pay(proposal_hash, &mut proposal_info, &payees, &tally)?;
+		} else {
+			Proposal::<T, I>::insert(proposal_hash, &proposal_info);
+		}
+		Ok(matching)
+	}
+
+	/// Square root of an amount in fixed point, with three decimals.
+	pub fn fixed_sqrt(amount: BalanceIn<T, I>) -> u128 {
+		amount.saturated_into::<u128>().saturating_mul(ROOT_SCALE).integer_sqrt()
+	}
+
+	/// Share the matching pool between proposals in proportion to the square of the sum of the
+	/// square roots of their contributions. The rounding dust stays in the pot.
+	pub fn quadratic_matches(
+		roots: &[u128],
+		matching_pool: BalanceIn<T, I>,
+	) -> Vec<BalanceIn<T, I>> {
+		let weights: Vec<u128> = roots.iter().map(|root| root.saturating_mul(*root)).collect();
+		let total = weights.iter().fold(0u128, |total, weight| total.saturating_add(*weight));
+		weights
+			.into_iter()
+			.map(|weight| match total {
+				0 => Zero::zero(),
+				_ => Perquintill::from_rational(weight, total).mul_floor(matching_pool),
+			})
+			.collect()
+	}
+
+	/// Give the funds escrowed for a proposal back to their donors.
+	fn refund_escrow(proposal_hash: &T::Hash, proposal_info: &mut ProposalInfoOf<T, I>) {
+		for (donor, amount) in Escrows::<T, I>::drain_prefix(proposal_hash) {
+			T::Currency::unreserve(&donor, amount);
+			Self::deposit_event(Event::EscrowRefunded {
+				proposal_hash: *proposal_hash,
+				donor,
+				amount,
+			});
+		}
+		proposal_info.escrowed = Zero::zero();
+		proposal_info.gap = proposal_info.amount;
+	}
+
+	/// Add a proposal to the sorted list of a secondary index.
+	fn insert_index<S, K>(key: K, proposal_index: ProposalIndex)
+	where
+		S: StorageMap<K, Vec<ProposalIndex>, Query = Vec<ProposalIndex>>,
+		K: FullEncode,
+	{
+		S::mutate(key, |indices| {
+			if let Err(location) = indices.binary_search(&proposal_index) {
+				indices.insert(location, proposal_index);
+			}
+		});
+	}
+
+	/// Remove a proposal from the sorted list of a secondary index.
+	fn remove_index<S, K>(key: K, proposal_index: ProposalIndex)
+	where
+		S: StorageMap<K, Vec<ProposalIndex>, Query = Vec<ProposalIndex>>,
+		K: FullEncode,
+	{
+		S::mutate_exists(key, |maybe_indices| {
+			if let Some(indices) = maybe_indices {
+				if let Ok(location) = indices.binary_search(&proposal_index) {
+					indices.remove(location);
+				}
+				if indices.is_empty() {
+					*maybe_indices = None;
+				}
+			}
+		});
+	}
+
+	/// Move a proposal from its previous payees to its new payees in the beneficiary index.
+	fn reindex_beneficiaries(
+		proposal_index: ProposalIndex,
+		old_payees: &[(T::AccountId, Perbill)],
+		new_payees: &[(T::AccountId, Perbill)],
+	) {
+		for (payee, _) in old_payees {
+			Self::remove_index::<ProposalsByBeneficiary<T, I>, _>(payee.clone(), proposal_index);
+		}
+		for (payee, _) in new_payees {
+			Self::insert_index::<ProposalsByBeneficiary<T, I>, _>(payee.clone(), proposal_index);
+		}
+	}
+
+	/// Page through a secondary index, returning up to `limit` proposals from index `start`.
+	pub fn page(
+		indices: Vec<ProposalIndex>,
+		start: ProposalIndex,
+		limit: u32,
+	) -> Vec<(ProposalIndex, T::Hash)> {
+		let from = indices.partition_point(|index| *index < start);
+		indices
+			.into_iter()
+			.skip(from)
+			.take(limit as usize)
+			.filter_map(|index| ProposalHashes::<T, I>::get(index).map(|hash| (index, hash)))
+			.collect()
+	}
+
+	/// Donors who gave the most to the pot, up to `limit` of them, with their total.
+	/// Donors with the same total are ordered by account.
+	pub fn top_donors(limit: u32) -> Vec<(T::AccountId, BalanceIn<T, I>)> {
+		let mut donors: Vec<_> = DonorTotals::<T, I>::iter().collect();
+		donors.sort_by(|(a, a_total), (b, b_total)| b_total.cmp(a_total).then_with(|| a.cmp(b)));
+		donors.truncate(limit as usize);
+		donors
+	}
+
+	pub fn resolve(proposal: ProposalRef<T::Hash>) -> Result<T::Hash, DispatchError> {
+		match proposal {
+			ProposalRef::Index(index) =>
+				Ok(ProposalHashes::<T, I>::get(index).ok_or(Error::<T, I>::ProposalMissing)?),
+			ProposalRef::Hash(hash) => Ok(hash),
+		}
+	}
+
+	/// Hash identifying the proposal with the given index.
+	pub fn proposal_id(index: ProposalIndex) -> T::Hash {
+		T::Hashing::hash_of(&(b"proposal", index))
+	}
+
+	/// Check a member can submit a proposal with the given document: the member stays under the
+	/// maximum of open proposals, waited long enough since the last submission and the document
+	/// wasn't rejected recently.
+	pub fn ensure_can_submit(who: &T::AccountId, document: &T::Hash) -> Result<(), Error<T, I>> {
+		let now = frame_system::Pallet::<T>::block_number();
+
+		ensure!(
+			OpenProposals::<T, I>::get(who) < T::MaxOpenProposals::get(),
+			Error::<T, I>::TooManyOpenProposals
+		);
+		if let Some(last) = LastSubmission::<T, I>::get(who) {
+			ensure!(now >= last + T::SubmissionGap::get().into(), Error::<T, I>::SubmissionTooSoon);
+		}
+		Self::ensure_not_rejected(document)
+	}
+
+	/// Check the document wasn't rejected less than `ResubmissionCooldown` blocks ago.
+	fn ensure_not_rejected(document: &T::Hash) -> Result<(), Error<T, I>> {
+		if let Some(rejected_at) = RejectedDocuments::<T, I>::get(document) {
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				now >= rejected_at + T::ResubmissionCooldown::get().into(),
+				Error::<T, I>::ResubmissionCooldown
+			);
+		}
+		Ok(())
+	}
+
+	/// Remove a proposal along with its votes and indexes, but not its recusals.
+	fn remove_proposal(proposal_hash: &T::Hash, proposal_info: ProposalInfoOf<T, I>) {
+		let proposal_index = proposal_info.index;
+		let payees = FundSeekerAccounts::<T, I>::take(proposal_hash);
+		Self::reindex_beneficiaries(proposal_index, &payees, &[]);
+		Self::remove_index::<ProposalsByProposer<T, I>, _>(proposal_info.proposer, proposal_index);
+		Self::remove_index::<ProposalsByStatus<T, I>, _>(proposal_info.status, proposal_index);
+		ProposalHashes::<T, I>::remove(proposal_index);
+
+		Proposal::<T, I>::remove(proposal_hash);
+		Voting::<T, I>::remove(proposal_hash);
+		Approvers::<T, I>::remove(proposal_hash);
+		ProposalVersions::<T, I>::remove(proposal_hash);
+		RevealStart::<T, I>::remove(proposal_hash);
+		VotingDeadline::<T, I>::remove(proposal_hash);
+		ProposalCalls::<T, I>::remove(proposal_hash);
+		CallWeights::<T, I>::remove(proposal_hash);
+		ExecutionResults::<T, I>::remove(proposal_hash);
+		T::Preimages::unrequest_preimage(&proposal_info.document);
+
+		Self::deposit_event(Event::ProposalPruned {
+			proposal_index,
+			proposal_hash: *proposal_hash,
+		});
+	}
+
+	/// Store a new proposal along with its empty votes, returns the hash identifying it.
+	fn insert_proposal(
+		who: T::AccountId,
+		title: Vec<u8>,
+		document: T::Hash,
+		amount: BalanceIn<T, I>,
+		payees: Vec<(T::AccountId, Perbill)>,
+	) -> Result<T::Hash, DispatchError> {
+		// member should be present in community members list
+		ensure!(T::CommunityProvider::contains(&who), Error::<T, I>::MemberIsNotPresentInCommunity);
+		Self::ensure_can_submit(&who, &document)?;
+		ensure!(title.len() <= T::MaxTitleLength::get() as usize, Error::<T, I>::TitleTooLong);
+		// The document should be available on-chain for the committee to review.
+		ensure!(T::Preimages::have_preimage(&document), Error::<T, I>::DocumentMissing);
+
+		// The proposal is identified by its index, which is never reused, so its hash is fresh.
+		// The document is only metadata.
+		let proposal_index = ProposalCount::<T, I>::get();
+		let proposal_hash = Self::proposal_id(proposal_index);
+
+		// Without payees the proposer receives the whole amount.
+		let payees = if payees.is_empty() { vec![(who.clone(), Perbill::one())] } else { payees };
+		Self::ensure_valid_payees(&payees)?;
+
+		OpenProposals::<T, I>::mutate(&who, |open| *open = open.saturating_add(1));
+		LastSubmission::<T, I>::insert(&who, frame_system::Pallet::<T>::block_number());
+		Self::insert_index::<ProposalsByProposer<T, I>, _>(who.clone(), proposal_index);
+		Self::insert_index::<ProposalsByStatus<T, I>, _>(ProposalStatus::Open, proposal_index);
+		Self::reindex_beneficiaries(proposal_index, &[], &payees);
+
+		let info = {
+			ProposalInfo {
+				proposer: who,
+				amount,
+				title,
+				status: ProposalStatus::Open,
+				index: proposal_index,
+				document,
+				version: 0,
+				escrowed: Zero::zero(),
+				gap: amount,
+			}
+		};
+		// Add Proposal
+		<Proposal<T, I>>::insert(proposal_hash, info);
+
+		// initially the votes will be null for any proposal.
+		let votes = { Votes { ayes: vec![], nays: vec![], abstains: vec![] } };
+		<Voting<T, I>>::insert(proposal_hash, votes);
+
+		// Secret ballots have a commit and a reveal phase starting with the proposal.
+		if T::SecretBallots::get() {
+			let reveal_start =
+				frame_system::Pallet::<T>::block_number() + T::CommitDuration::get().into();
+			RevealStart::<T, I>::insert(proposal_hash, reveal_start);
+			Self::schedule_settlement(
+				&proposal_hash,
+				reveal_start + T::RevealDuration::get().into(),
+			);
+		}
+
+		<FundSeekerAccounts<T, I>>::insert(proposal_hash, payees);
+		ProposalHashes::<T, I>::insert(proposal_index, proposal_hash);
+		ProposalCount::<T, I>::put(proposal_index.saturating_add(1));
+		T::Preimages::request_preimage(&document);
+		Self::deposit_event(Event::ProposalAdded { proposal_index, proposal_hash, document });
+		Ok(proposal_hash)
+	}
+
+	/// Check the payees are distinct accounts whose shares add up to 100%.
+	fn ensure_valid_payees(payees: &[(T::AccountId, Perbill)]) -> DispatchResult {
+		ensure!(payees.len() <= T::MaxPayees::get() as usize, Error::<T, I>::TooManyPayees);
+
+		let total = payees.iter().map(|(_, share)| share.deconstruct() as u64).sum::<u64>();
+		ensure!(total == Perbill::one().deconstruct() as u64, Error::<T, I>::InvalidPayeeShares);
+
+		let mut accounts: Vec<&T::AccountId> = payees.iter().map(|(payee, _)| payee).collect();
+		accounts.sort();
+		accounts.dedup();
+		ensure!(accounts.len() == payees.len(), Error::<T, I>::InvalidPayeeShares);
+		Ok(())
+	}
+
+	/// Add the account to the community member's list.
+	fn insert_community_member(who: T::AccountId) -> DispatchResult {
+		// fetch the existing members from the community list.
+		let mut members = CommunityMembers::<T, I>::get();
+		// Search the new member in the existing member's list.
+		let location = members
+			.binary_search(&who)
+			.err()
+			.ok_or(Error::<T, I>::AlreadyMemberOfCommunity)?;
+
+		// add the new member in the community member's list.
+		members.insert(location, who);
+
+		CommunityMembers::<T, I>::put(&members);
+
+		Self::deposit_event(Event::MemberAdded);
+		Ok(())
+	}
+
+	/// Admit a pending applicant into the community and return its deposit.
+	fn admit(applicant: T::AccountId) -> DispatchResult {
+		let application =
+			Applications::<T, I>::take(&applicant).ok_or(Error::<T, I>::ApplicationMissing)?;
+		T::Currency::unreserve(&applicant, application.deposit);
+
+		Self::insert_community_member(applicant.clone())?;
+		Self::deposit_event(Event::ApplicationApproved { applicant });
+		Ok(())
+	}
+
+	/// Count the approval votes and replace the committee with the most approved candidates.
+	/// Ties are broken in favour of the lower account, every bond is returned. Returns the
+	/// weight used.
+	pub fn elect_committee() -> Weight {
+		let candidates = Candidates::<T, I>::take();
+		let mut tally: Vec<(T::AccountId, MemberCount)> =
+			candidates.iter().map(|(candidate, _)| (candidate.clone(), 0)).collect();
+
+		let mut voters = 0u64;
+		for (_voter, votes) in ElectionVotes::<T, I>::drain() {
+			voters = voters.saturating_add(1);
+			for vote in votes.iter() {
+				if let Ok(location) = tally.binary_search_by(|(candidate, _)| candidate.cmp(vote)) {
+					tally[location].1 += 1;
+				}
+			}
+		}
+
+		for (candidate, bond) in candidates.iter() {
+			T::Currency::unreserve(candidate, *bond);
+		}
+		// Every vote and every bond is read and written once.
+		let weight = T::DbWeight::get().reads_writes(2, 2).saturating_add(
+			T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_mul(voters.saturating_add(candidates.len() as u64)),
+		);
+
+		// `sort_by` is stable, so candidates with the same approvals stay ordered by account.
+		tally.sort_by(|a, b| b.1.cmp(&a.1));
+		let mut members: Vec<T::AccountId> = tally
+			.into_iter()
+			.filter(|(_, approvals)| *approvals > 0)
+			.take(T::DesiredMembers::get() as usize)
+			.map(|(candidate, _)| candidate)
+			.collect();
+
+		if members.is_empty() {
+			Self::deposit_event(Event::EmptyTerm);
+			return weight
+		}
+
+		members.sort();
+		CommitteeMembers::<T, I>::put(&members);
+		Self::deposit_event(Event::NewTerm { members });
+		weight
 	}
 }
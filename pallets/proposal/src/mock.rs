@@ -1,10 +1,22 @@
 use crate as pallet_proposal;
-use frame_support::traits::{ConstU16, ConstU64};
+use crate::{
+	AmendmentPolicy, BudgetAllocation, BudgetRanking, Committee, Community, OnProposalOutcome,
+	ProposalStatus, ScheduleSettlement, Tally,
+};
+use frame_support::{
+	parameter_types,
+	traits::{
+		ConstBool, ConstU16, ConstU32, ConstU64, EitherOfDiverse, Get, OnInitialize,
+		PreimageProvider, SortedMembers,
+	},
+};
 use frame_system as system;
-use sp_core::{parameter_types, H256};
+use frame_system::EnsureRoot;
+use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -18,7 +30,9 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system,
+		Balances: pallet_balances,
 		ProposalPallet: pallet_proposal,
+		BountyPallet: pallet_proposal::<Instance1>,
 	}
 );
 
@@ -40,7 +54,7 @@ impl system::Config for Test {
 	type BlockHashCount = ConstU64<250>;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -49,17 +63,304 @@ impl system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type RuntimeEvent = RuntimeEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
 parameter_types! {
+	pub static ExistentialDeposit: u64 = 1;
 	pub const TimeDuration: u32 = 10;
+	pub const BountyTimeDuration: u32 = 5;
+	pub const TermDuration: u32 = 20;
+	pub const CandidacyBond: u64 = 100;
+	pub const DesiredMembers: u32 = 2;
+	pub const ApplicationDeposit: u64 = 50;
+	pub const VouchThreshold: u32 = 2;
+	pub const MaxCallLength: u32 = 128;
+	pub const MaxPayees: u32 = 4;
+	pub const Quorum: Perbill = Perbill::from_percent(50);
+	pub static SecretBallots: bool = false;
+	pub const CommitDuration: u32 = 5;
+	pub const RevealDuration: u32 = 5;
+	pub const CommitDeposit: u64 = 10;
+	pub static AmendmentVotePolicy: AmendmentPolicy = AmendmentPolicy::ResetVotes;
+	pub static MaxOpenProposals: u32 = 10;
+	pub static SubmissionGap: u32 = 0;
+	pub static ResubmissionCooldown: u32 = 0;
+	pub const UrgentVoteWindow: u32 = 2;
+	pub const UrgentVotePriority: u64 = 100;
+	pub static MaxFreeVotes: u32 = 2;
+	pub static UseScheduler: bool = false;
+	pub static Scheduled: Vec<(H256, u64)> = vec![];
+	pub static MissingDocuments: Vec<H256> = vec![];
+	pub static RequestedDocuments: Vec<H256> = vec![];
+	pub static Outcomes: Vec<(H256, ProposalStatus)> = vec![];
+	pub static ExternalCommittee: Option<Vec<u64>> = None;
+	pub static BudgetPeriod: u32 = 0;
+	pub static BudgetRankingPolicy: BudgetRanking = BudgetRanking::ApprovalScore;
+	pub static BudgetAllocationPolicy: BudgetAllocation = BudgetAllocation::Greedy;
+}
+
+/// The committee kept by the pallet, or `ExternalCommittee` when it is set.
+pub struct TestCommittee;
+impl SortedMembers<u64> for TestCommittee {
+	fn sorted_members() -> Vec<u64> {
+		ExternalCommittee::get().unwrap_or_else(Committee::<Test>::sorted_members)
+	}
+}
+
+/// The committee is external while `ExternalCommittee` is set.
+pub struct CommitteeIsExternal;
+impl Get<bool> for CommitteeIsExternal {
+	fn get() -> bool {
+		ExternalCommittee::get().is_some()
+	}
+}
+
+/// Records every status transition into `Outcomes`.
+pub struct RecordOutcomes;
+impl<Balance> OnProposalOutcome<u64, H256, Balance> for RecordOutcomes {
+	fn on_proposal_outcome(
+		proposal_hash: &H256,
+		status: ProposalStatus,
+		_beneficiary: &u64,
+		_amount: Balance,
+		_tally: &Tally,
+	) {
+		Outcomes::mutate(|outcomes| outcomes.push((*proposal_hash, status)));
+	}
+}
+
+/// Records the scheduled settlements into `Scheduled` when `UseScheduler` is set, otherwise
+/// falls back to the `TransferTime` hook.
+pub struct RecordSettlement;
+impl ScheduleSettlement<u64, H256> for RecordSettlement {
+	fn schedule(proposal_hash: &H256, when: u64) -> bool {
+		if !UseScheduler::get() {
+			return false
+		}
+		Self::cancel(proposal_hash);
+		Scheduled::mutate(|scheduled| scheduled.push((*proposal_hash, when)));
+		true
+	}
+
+	fn cancel(proposal_hash: &H256) {
+		Scheduled::mutate(|scheduled| scheduled.retain(|(hash, _)| hash != proposal_hash));
+	}
+}
+
+/// Every document is noted, except for those in `MissingDocuments`. Requests are recorded into
+/// `RequestedDocuments`.
+pub struct TestPreimages;
+impl PreimageProvider<H256> for TestPreimages {
+	fn have_preimage(hash: &H256) -> bool {
+		!MissingDocuments::get().contains(hash)
+	}
+
+	fn get_preimage(hash: &H256) -> Option<Vec<u8>> {
+		Self::have_preimage(hash).then(|| hash.as_bytes().to_vec())
+	}
+
+	fn preimage_requested(hash: &H256) -> bool {
+		RequestedDocuments::get().contains(hash)
+	}
+
+	fn request_preimage(hash: &H256) {
+		RequestedDocuments::mutate(|requested| requested.push(*hash));
+	}
+
+	fn unrequest_preimage(hash: &H256) {
+		RequestedDocuments::mutate(|requested| {
+			if let Some(position) = requested.iter().position(|requested| requested == hash) {
+				requested.remove(position);
+			}
+		});
+	}
 }
 
 impl pallet_proposal::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
-	type Currency = ();
+	type Currency = Balances;
 	type TimeDuration = TimeDuration;
+	type CandidacyBond = CandidacyBond;
+	type TermDuration = TermDuration;
+	type DesiredMembers = DesiredMembers;
+	type ApplicationDeposit = ApplicationDeposit;
+	type VouchThreshold = VouchThreshold;
+	type AdmissionOrigin = EitherOfDiverse<EnsureRoot<u64>, pallet_proposal::EnsureProposal<Test>>;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type MaxCallLength = MaxCallLength;
+	type OnProposalOutcome = RecordOutcomes;
+	type MaxPayees = MaxPayees;
+	type Quorum = Quorum;
+	type SecretBallots = SecretBallots;
+	type CommitDuration = CommitDuration;
+	type RevealDuration = RevealDuration;
+	type CommitDeposit = CommitDeposit;
+	type AmendmentPolicy = AmendmentVotePolicy;
+	type MaxOpenProposals = MaxOpenProposals;
+	type SubmissionGap = SubmissionGap;
+	type ResubmissionCooldown = ResubmissionCooldown;
+	type UrgentVoteWindow = UrgentVoteWindow;
+	type UrgentVotePriority = UrgentVotePriority;
+	type MaxFreeVotes = MaxFreeVotes;
+	type Settlement = RecordSettlement;
+	type Preimages = TestPreimages;
+	type MaxTitleLength = ConstU32<32>;
+	type CommunityProvider = Community<Test>;
+	type CommitteeProvider = TestCommittee;
+	type CommitteeIsExternal = CommitteeIsExternal;
+	type BudgetPeriod = BudgetPeriod;
+	type BudgetRanking = BudgetRankingPolicy;
+	type BudgetAllocation = BudgetAllocationPolicy;
+	type RankingOrigin = EnsureRoot<u64>;
+	type MaxRoundProposals = ConstU32<3>;
+}
+
+/// A second instance of the pallet, with its own pot, committee and voting period.
+impl pallet_proposal::Config<pallet_proposal::Instance1> for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type TimeDuration = BountyTimeDuration;
+	type CandidacyBond = CandidacyBond;
+	type TermDuration = TermDuration;
+	type DesiredMembers = DesiredMembers;
+	type ApplicationDeposit = ApplicationDeposit;
+	type VouchThreshold = VouchThreshold;
+	type AdmissionOrigin = EnsureRoot<u64>;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type MaxCallLength = MaxCallLength;
+	type OnProposalOutcome = ();
+	type MaxPayees = MaxPayees;
+	type Quorum = Quorum;
+	type SecretBallots = SecretBallots;
+	type CommitDuration = CommitDuration;
+	type RevealDuration = RevealDuration;
+	type CommitDeposit = CommitDeposit;
+	type AmendmentPolicy = AmendmentVotePolicy;
+	type MaxOpenProposals = MaxOpenProposals;
+	type SubmissionGap = SubmissionGap;
+	type ResubmissionCooldown = ResubmissionCooldown;
+	type UrgentVoteWindow = UrgentVoteWindow;
+	type UrgentVotePriority = UrgentVotePriority;
+	type MaxFreeVotes = MaxFreeVotes;
+	type Settlement = ();
+	type Preimages = TestPreimages;
+	type MaxTitleLength = ConstU32<32>;
+	type CommunityProvider = Community<Test, pallet_proposal::Instance1>;
+	type CommitteeProvider = Committee<Test, pallet_proposal::Instance1>;
+	type CommitteeIsExternal = ConstBool<false>;
+	type BudgetPeriod = ConstU32<0>;
+	type BudgetRanking = BudgetRankingPolicy;
+	type BudgetAllocation = BudgetAllocationPolicy;
+	type RankingOrigin = EnsureRoot<u64>;
+	type MaxRoundProposals = ConstU32<3>;
+}
+
+/// Account of the pot seeded by `ExtBuilder::pot`.
+pub const POT: u64 = 100;
+
+/// Builds the test externalities, with accounts 1 to 10 endowed and optionally community
+/// members, a committee and a funded pot.
+pub struct ExtBuilder {
+	balances: Vec<(u64, u64)>,
+	community: Vec<u64>,
+	committee: Vec<u64>,
+	pot: Option<u64>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: (1..=10).map(|who| (who, 10_000)).collect(),
+			community: vec![],
+			committee: vec![],
+			pot: None,
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn community(mut self, members: Vec<u64>) -> Self {
+		self.community = members;
+		self
+	}
+
+	/// Committee members are also added to the community.
+	pub fn committee(mut self, members: Vec<u64>) -> Self {
+		self.committee = members;
+		self
+	}
+
+	/// Set `POT` as the pot account, with the given balance.
+	pub fn pot(mut self, balance: u64) -> Self {
+		self.pot = Some(balance);
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut balances = self.balances;
+		balances.extend(self.pot.map(|balance| (POT, balance)));
+		pallet_balances::GenesisConfig::<Test> { balances }
+			.assimilate_storage(&mut storage)
+			.unwrap();
+
+		let mut ext: sp_io::TestExternalities = storage.into();
+		ext.execute_with(|| {
+			let mut committee = self.committee;
+			committee.sort();
+			committee.dedup();
+			let mut community = self.community;
+			community.extend(committee.iter().cloned());
+			community.sort();
+			community.dedup();
+			pallet_proposal::CommunityMembers::<Test>::put(community);
+			pallet_proposal::CommitteeMembers::<Test>::put(committee);
+			if self.pot.is_some() {
+				pallet_proposal::PotAccount::<Test>::put(vec![POT]);
+			}
+		});
+		ext
+	}
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	ExtBuilder::default().build()
+}
+
+/// Advance to block `n`, running the `on_initialize` hooks of every block on the way.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		AllPalletsWithSystem::on_initialize(next);
+	}
+}
+
+/// Events deposited by `ProposalPallet` so far.
+pub fn proposal_events() -> Vec<pallet_proposal::Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::ProposalPallet(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+/// Assert `ProposalPallet` deposited the event.
+pub fn assert_proposal_event(event: pallet_proposal::Event<Test>) {
+	assert!(proposal_events().contains(&event), "event {:?} not found", event);
 }
@@ -0,0 +1,181 @@
+//! Random sequences of calls against the mock runtime, checking the invariants of the pallet
+//! after every step. Failing sequences are saved in `proptest-regressions` and replayed first.
+
+use crate::{mock::*, ProposalIndex, ProposalRef, ProposalStatus, Vote};
+use frame_support::{dispatch::Dispatchable, traits::Currency, weights::Weight};
+use proptest::{
+	collection::vec,
+	prelude::*,
+	test_runner::{FileFailurePersistence, TestCaseError},
+};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+const ACCOUNTS: u64 = 10;
+const POT_BALANCE: u64 = 5_000;
+const STATUSES: [ProposalStatus; 7] = [
+	ProposalStatus::Open,
+	ProposalStatus::Approved,
+	ProposalStatus::Rejected,
+	ProposalStatus::QuorumNotReached,
+	ProposalStatus::Paid,
+	ProposalStatus::Executed,
+	ProposalStatus::Deferred,
+];
+
+#[derive(Clone, Debug)]
+enum Action {
+	Join(u64),
+	AddCommittee(u64),
+	Propose { who: u64, amount: u64 },
+	Vote { who: u64, proposal: ProposalIndex, vote: Vote },
+	Fund { who: u64, amount: u64 },
+	Close { proposal: ProposalIndex },
+	Advance(u64),
+}
+
+fn account() -> impl Strategy<Value = u64> {
+	1..=ACCOUNTS
+}
+
+fn action() -> impl Strategy<Value = Action> {
+	prop_oneof![
+		account().prop_map(Action::Join),
+		account().prop_map(Action::AddCommittee),
+		(account(), 1..3_000u64).prop_map(|(who, amount)| Action::Propose { who, amount }),
+		(account(), 0..8u32, prop_oneof![Just(Vote::Aye), Just(Vote::Nay), Just(Vote::Abstain)])
+			.prop_map(|(who, proposal, vote)| Action::Vote { who, proposal, vote }),
+		(account(), 1..2_000u64).prop_map(|(who, amount)| Action::Fund { who, amount }),
+		(0..8u32).prop_map(|proposal| Action::Close { proposal }),
+		(1..12u64).prop_map(Action::Advance),
+	]
+}
+
+/// What the pot and the balances should be, given the calls which succeeded so far.
+struct Model {
+	total: u64,
+	pot: u64,
+	paid: Vec<ProposalIndex>,
+}
+
+impl Model {
+	fn new() -> Self {
+		Model { total: total_balance(), pot: POT_BALANCE, paid: vec![] }
+	}
+}
+
+fn total_balance() -> u64 {
+	(1..=ACCOUNTS).chain([POT]).map(Balances::total_balance).sum()
+}
+
+fn dispatch(origin: RuntimeOrigin, call: crate::Call<Test>) -> bool {
+	RuntimeCall::ProposalPallet(call).dispatch(origin).is_ok()
+}
+
+fn apply(action: Action, model: &mut Model) {
+	match action {
+		Action::Join(who) => {
+			dispatch(RuntimeOrigin::root(), crate::Call::add_community_member { who });
+		},
+		Action::AddCommittee(who) => {
+			dispatch(RuntimeOrigin::root(), crate::Call::add_committee_member { who });
+		},
+		Action::Propose { who, amount } => {
+			let document = BlakeTwo256::hash_of(&ProposalPallet::proposal_count());
+			let call =
+				crate::Call::add_proposal { title: vec![], document, amount, payees: vec![] };
+			dispatch(RuntimeOrigin::signed(who), call);
+		},
+		Action::Vote { who, proposal, vote } => {
+			let call = crate::Call::approve_proposal {
+				proposal: ProposalRef::Index(proposal),
+				approve: vote,
+			};
+			dispatch(RuntimeOrigin::signed(who), call);
+		},
+		Action::Fund { who, amount } => {
+			if dispatch(
+				RuntimeOrigin::signed(who),
+				crate::Call::fund_pot_account { amount, memo: None },
+			) {
+				model.pot += amount;
+			}
+		},
+		Action::Close { proposal } => {
+			let call = crate::Call::close {
+				proposal: ProposalRef::Index(proposal),
+				call_weight_bound: Weight::zero(),
+			};
+			dispatch(RuntimeOrigin::signed(1), call);
+		},
+		Action::Advance(blocks) => run_to_block(System::block_number() + blocks),
+	}
+}
+
+fn is_sorted_unique<T: Ord>(items: &[T]) -> bool {
+	items.windows(2).all(|pair| pair[0] < pair[1])
+}
+
+fn check_invariants(model: &mut Model) -> Result<(), TestCaseError> {
+	// Funds only move between accounts.
+	prop_assert_eq!(total_balance(), model.total);
+
+	// A paid proposal stays paid, and is paid exactly once out of the pot.
+	let mut paid = vec![];
+	for index in 0..ProposalPallet::proposal_count() {
+		let hash = ProposalPallet::proposal_id(index);
+		let proposal = match ProposalPallet::proposal(hash) {
+			Some(proposal) => proposal,
+			None => continue,
+		};
+		if proposal.status == ProposalStatus::Paid {
+			paid.push(index);
+			if !model.paid.contains(&index) {
+				model.pot -= proposal.amount;
+			}
+		}
+		prop_assert!(is_sorted_unique(&ProposalPallet::approvers(hash)));
+	}
+	prop_assert!(model.paid.iter().all(|index| paid.contains(index)));
+	let transfers = proposal_events()
+		.into_iter()
+		.filter(|event| *event == crate::Event::FundTransfer)
+		.count();
+	prop_assert_eq!(transfers, paid.len());
+	prop_assert_eq!(Balances::free_balance(POT), model.pot);
+	model.paid = paid;
+
+	// Member lists and indexes are sorted without duplicates.
+	prop_assert!(is_sorted_unique(&ProposalPallet::community_members()));
+	prop_assert!(is_sorted_unique(&ProposalPallet::committee_members()));
+	for status in STATUSES {
+		prop_assert!(is_sorted_unique(&ProposalPallet::proposals_by_status(status)));
+	}
+	for who in 1..=ACCOUNTS {
+		prop_assert!(is_sorted_unique(&ProposalPallet::proposals_by_proposer(who)));
+	}
+	Ok(())
+}
+
+proptest! {
+	#![proptest_config(ProptestConfig {
+		cases: 128,
+		failure_persistence: Some(Box::new(FileFailurePersistence::SourceParallel(
+			"proptest-regressions",
+		))),
+		..ProptestConfig::default()
+	})]
+
+	#[test]
+	fn invariants_hold_for_random_calls(actions in vec(action(), 1..60)) {
+		let mut ext = ExtBuilder::default().pot(POT_BALANCE).build();
+		ext.execute_with(|| -> Result<(), TestCaseError> {
+			run_to_block(1);
+			let mut model = Model::new();
+			for action in actions.iter() {
+				apply(action.clone(), &mut model);
+				check_invariants(&mut model)?;
+			}
+			Ok(())
+		})?;
+	}
+}
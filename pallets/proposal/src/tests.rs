@@ -1,6 +1,19 @@
-use crate::{mock::*, Error, Vote, Votes};
-use frame_support::{assert_noop, assert_ok};
-use sp_runtime::traits::Hash;
+use crate::{
+	mock::*, AmendmentPolicy, BudgetAllocation, BudgetRanking, CheckProposalMembership,
+	CheckProposalRate, Donation, Error, Instance1, ProposalRef, ProposalStatus, ProposalVersion,
+	Tally, TransferTime, Vote, Votes,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, GetDispatchInfo, Pays},
+	traits::{ChangeMembers, Hooks, InitializeMembers},
+	weights::Weight,
+};
+use sp_runtime::{
+	traits::{Hash, SignedExtension},
+	transaction_validity::{InvalidTransaction, TransactionValidity},
+	Perbill,
+};
 
 pub type HashType = <Test as frame_system::Config>::Hash;
 pub type Hashing = <Test as frame_system::Config>::Hashing;
@@ -10,18 +23,28 @@ fn add_community_member_successfully() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_eq!(ProposalPallet::community_members(), vec![TEST_ACCOUNT]);
 	});
 }
 
+#[test]
+fn add_community_member_from_extrinsic_fail() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProposalPallet::add_community_member(RuntimeOrigin::signed(1), 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
 #[test]
 fn add_committee_member_passed() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
@@ -34,7 +57,7 @@ fn add_committee_member_from_extrinsic_fail() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_noop!(
 			ProposalPallet::add_committee_member(RuntimeOrigin::signed(TEST_ACCOUNT), 1),
@@ -48,13 +71,23 @@ fn add_proposal_successfully() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// Create a proposal
-		let hash = HashType::from(Hashing::hash_of(&42));
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 		let title = Vec::new();
-		assert_ok!(ProposalPallet::add_proposal(RuntimeOrigin::signed(1), title, hash, 1000));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(1),
+			title,
+			document,
+			1000,
+			vec![]
+		));
 
-		assert_eq!(ProposalPallet::voting(hash), Some(Votes { ayes: vec![], nays: vec![] }));
+		assert_eq!(
+			ProposalPallet::voting(hash),
+			Some(Votes { ayes: vec![], nays: vec![], abstains: vec![] })
+		);
 	});
 }
 
@@ -63,38 +96,87 @@ fn add_proposal_when_not_present_in_community_fail() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// Create a proposal
-		let hash = HashType::from(Hashing::hash_of(&42));
+		let document = HashType::from(Hashing::hash_of(&42));
 
 		let title = Vec::new();
 
 		assert_noop!(
-			ProposalPallet::add_proposal(RuntimeOrigin::signed(1), title, hash, 1000),
+			ProposalPallet::add_proposal(RuntimeOrigin::signed(1), title, document, 1000, vec![]),
 			Error::<Test>::MemberIsNotPresentInCommunity
 		);
 	});
 }
 
 #[test]
-fn add_same_proposal_multiple_times_fail() {
+fn add_same_document_multiple_times_creates_distinct_proposals() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
-		// let proposal = make_proposal(42);
-		let hash = HashType::from(Hashing::hash_of(&42));
+		System::set_block_number(1);
+		let document = HashType::from(Hashing::hash_of(&42));
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 2));
 		let title = Vec::new();
 
 		assert_ok!(ProposalPallet::add_proposal(
 			RuntimeOrigin::signed(1),
 			title.clone(),
-			hash,
-			1000
+			document,
+			1000,
+			vec![]
+		));
+		// Another member can submit a proposal with the same document.
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(2),
+			title,
+			document,
+			1000,
+			vec![]
+		));
+
+		assert_eq!(ProposalPallet::proposal_count(), 2);
+		let first = ProposalPallet::proposal_hashes(0).unwrap();
+		let second = ProposalPallet::proposal_hashes(1).unwrap();
+		assert_ne!(first, second);
+		assert_eq!(ProposalPallet::proposal(first).unwrap().document, document);
+		assert_eq!(ProposalPallet::proposal(second).unwrap().document, document);
+		System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::ProposalAdded {
+			proposal_index: 1,
+			proposal_hash: second,
+			document,
+		}));
+	});
+}
+
+#[test]
+fn vote_on_proposal_by_index() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
 		));
 
 		assert_noop!(
-			ProposalPallet::add_proposal(RuntimeOrigin::signed(1), title, hash, 1000),
-			Error::<Test>::ProposalAlreadyExist
+			ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				ProposalRef::Index(1),
+				Vote::Aye
+			),
+			Error::<Test>::ProposalMissing
 		);
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(0),
+			Vote::Aye
+		));
+		assert_eq!(ProposalPallet::approvers(ProposalPallet::proposal_id(0)), vec![1]);
 	});
 }
 
@@ -103,22 +185,29 @@ fn approve_proposal_correctly() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// let proposal = make_proposal(42);
-		let hash = HashType::from(Hashing::hash_of(&42));
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
 		let title = Vec::new();
 
 		assert_ok!(ProposalPallet::add_proposal(
-			RuntimeOrigin::signed(1),
+			RuntimeOrigin::signed(9),
 			title.clone(),
-			hash,
-			1000
+			document,
+			1000,
+			vec![]
 		));
 
-		assert_ok!(ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash, Vote::Aye));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
 
 		assert_eq!(ProposalPallet::approvers(hash), vec![1]);
 	});
@@ -129,25 +218,32 @@ fn approve_same_proposal_multiple_times_fails() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		// let proposal = make_proposal(42);
-		let hash = HashType::from(Hashing::hash_of(&42));
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
 		let title = Vec::new();
 
 		assert_ok!(ProposalPallet::add_proposal(
-			RuntimeOrigin::signed(1),
+			RuntimeOrigin::signed(9),
 			title.clone(),
-			hash,
-			1000
+			document,
+			1000,
+			vec![]
 		));
 
-		assert_ok!(ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash, Vote::Aye));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
 
 		assert_noop!(
-			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash, Vote::Aye),
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Aye),
 			Error::<Test>::AlreadyApproved
 		);
 	});
@@ -160,12 +256,12 @@ fn approve_wrong_proposal_fails() {
 		// let proposal = make_proposal(42);
 		let hash = HashType::from(Hashing::hash_of(&42));
 		// Dispatch a signed extrinsic.
-		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::signed(1), TEST_ACCOUNT));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), TEST_ACCOUNT));
 
 		assert_noop!(
-			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash, Vote::Aye),
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Aye),
 			Error::<Test>::ProposalMissing
 		);
 	});
@@ -188,8 +284,2528 @@ fn fund_pot_account_by_member_not_in_community_fails() {
 	new_test_ext().execute_with(|| {
 		const TEST_ACCOUNT: <Test as frame_system::Config>::AccountId = 1;
 		assert_noop!(
-			ProposalPallet::fund_pot_account(RuntimeOrigin::signed(1), TEST_ACCOUNT, 1000),
+			ProposalPallet::fund_pot_account(RuntimeOrigin::signed(TEST_ACCOUNT), 1000, None),
+			Error::<Test>::MemberIsNotPresentInCommunity
+		);
+	});
+}
+
+#[test]
+fn submit_candidacy_by_member_not_in_community_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProposalPallet::submit_candidacy(RuntimeOrigin::signed(1)),
+			Error::<Test>::MemberIsNotPresentInCommunity
+		);
+	});
+}
+
+#[test]
+fn vote_for_account_which_is_not_candidate_fails() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 2));
+		assert_ok!(ProposalPallet::submit_candidacy(RuntimeOrigin::signed(1)));
+
+		assert_noop!(
+			ProposalPallet::vote_candidates(RuntimeOrigin::signed(2), vec![1, 2]),
+			Error::<Test>::NotACandidate
+		);
+	});
+}
+
+#[test]
+fn committee_elected_at_the_end_of_term() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=4 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::submit_candidacy(RuntimeOrigin::signed(1)));
+		assert_ok!(ProposalPallet::submit_candidacy(RuntimeOrigin::signed(2)));
+		assert_ok!(ProposalPallet::submit_candidacy(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_eq!(Balances::free_balance(1), 9_900);
+		// A candidate who renounces gets the bond back.
+		assert_ok!(ProposalPallet::submit_candidacy(RuntimeOrigin::signed(4)));
+		assert_eq!(Balances::reserved_balance(4), 100);
+		assert_ok!(ProposalPallet::renounce_candidacy(RuntimeOrigin::signed(4)));
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(4), 10_000);
+
+		assert_ok!(ProposalPallet::vote_candidates(RuntimeOrigin::signed(1), vec![3, 1]));
+		assert_ok!(ProposalPallet::vote_candidates(RuntimeOrigin::signed(2), vec![3]));
+		assert_ok!(ProposalPallet::vote_candidates(RuntimeOrigin::signed(4), vec![2, 1]));
+
+		// Nothing happens before the end of the term.
+		ProposalPallet::on_initialize(19);
+		assert_eq!(ProposalPallet::committee_members(), Vec::<u64>::new());
+
+		ProposalPallet::on_initialize(20);
+		// 1 and 3 have two approvals each, 2 only has one.
+		assert_eq!(ProposalPallet::committee_members(), vec![1, 3]);
+		assert!(ProposalPallet::candidates().is_empty());
+		assert_eq!(ProposalPallet::election_votes(1), Vec::<u64>::new());
+		// Elected or not, every candidate gets the bond back once the election is counted.
+		for who in 1..=3 {
+			assert_eq!(Balances::reserved_balance(who), 0);
+			assert_eq!(Balances::free_balance(who), 10_000);
+		}
+
+		// Once elected, the committee can't be extended by sudo anymore.
+		System::set_block_number(20);
+		assert_noop!(
+			ProposalPallet::add_committee_member(RuntimeOrigin::root(), 4),
+			Error::<Test>::CommitteeElected
+		);
+	});
+}
+
+#[test]
+fn applicant_admitted_after_enough_vouches() {
+	new_test_ext().execute_with(|| {
+		let metadata = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 2));
+
+		assert_ok!(ProposalPallet::apply_for_membership(RuntimeOrigin::signed(3), metadata));
+		assert_eq!(Balances::reserved_balance(3), 50);
+		assert_noop!(
+			ProposalPallet::apply_for_membership(RuntimeOrigin::signed(3), metadata),
+			Error::<Test>::AlreadyApplied
+		);
+
+		assert_ok!(ProposalPallet::vouch(RuntimeOrigin::signed(1), 3));
+		assert_noop!(
+			ProposalPallet::vouch(RuntimeOrigin::signed(1), 3),
+			Error::<Test>::AlreadyVouched
+		);
+		assert_eq!(ProposalPallet::community_members(), vec![1, 2]);
+
+		assert_ok!(ProposalPallet::vouch(RuntimeOrigin::signed(2), 3));
+		assert_eq!(ProposalPallet::community_members(), vec![1, 2, 3]);
+		assert_eq!(ProposalPallet::applications(3), None);
+		// The deposit is returned on admission.
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(3), 10_000);
+
+		// The admission origin admits without vouches, returning the deposit too.
+		assert_ok!(ProposalPallet::apply_for_membership(RuntimeOrigin::signed(4), metadata));
+		assert_eq!(Balances::free_balance(4), 9_950);
+		assert_ok!(ProposalPallet::approve_application(RuntimeOrigin::root(), 4));
+		assert_eq!(ProposalPallet::community_members(), vec![1, 2, 3, 4]);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(4), 10_000);
+	});
+}
+
+#[test]
+fn reject_application_removes_it() {
+	new_test_ext().execute_with(|| {
+		let metadata = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::apply_for_membership(RuntimeOrigin::signed(3), metadata));
+		assert_ok!(ProposalPallet::apply_for_membership(RuntimeOrigin::signed(4), metadata));
+		assert_eq!(Balances::reserved_balance(3), 50);
+		assert_eq!(Balances::free_balance(3), 9_950);
+
+		assert_noop!(
+			ProposalPallet::reject_application(RuntimeOrigin::signed(1), 3, true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		let issuance = Balances::total_issuance();
+		assert_ok!(ProposalPallet::reject_application(RuntimeOrigin::root(), 3, true));
+		// The slashed deposit is gone.
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(3), 9_950);
+		assert_eq!(Balances::total_issuance(), issuance - 50);
+		// A deposit which isn't slashed is refunded.
+		assert_ok!(ProposalPallet::reject_application(RuntimeOrigin::root(), 4, false));
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(Balances::free_balance(4), 10_000);
+
+		assert_eq!(ProposalPallet::applications(3), None);
+		assert_noop!(
+			ProposalPallet::vouch(RuntimeOrigin::signed(1), 3),
 			Error::<Test>::MemberIsNotPresentInCommunity
 		);
 	});
 }
+
+#[test]
+fn approved_call_proposal_is_dispatched() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		let call =
+			Box::new(RuntimeCall::ProposalPallet(crate::Call::add_community_member { who: 5 }));
+		let call_weight = call.get_dispatch_info().weight;
+		assert_ok!(ProposalPallet::add_call_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			call
+		));
+		assert_eq!(ProposalPallet::call_weight(hash), Some(call_weight));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+
+		// The hook approves the proposal, but leaves its call to be closed.
+		ProposalPallet::on_initialize(10);
+		assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Approved);
+		assert!(ProposalPallet::execution_result(hash).is_none());
+
+		assert_noop!(
+			ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()),
+			Error::<Test>::WrongCallWeightBound
+		);
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight));
+
+		assert_eq!(ProposalPallet::execution_result(hash), Some(Ok(())));
+		assert!(ProposalPallet::call_weight(hash).is_none());
+		assert_eq!(ProposalPallet::community_members(), vec![1, 5, 9]);
+		assert!(ProposalPallet::proposal_call(hash).is_none());
+		assert_eq!(
+			Outcomes::get(),
+			vec![(hash, ProposalStatus::Approved), (hash, ProposalStatus::Executed)]
+		);
+	});
+}
+
+#[test]
+fn proposal_with_nay_vote_rejected() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 2));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 2));
+
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+
+		ProposalPallet::on_initialize(10);
+
+		assert_eq!(Outcomes::get(), vec![(hash, ProposalStatus::Rejected)]);
+		// Settled proposals can't be voted anymore.
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(2), hash.into(), Vote::Aye),
+			Error::<Test>::ProposalNotOpen
+		);
+	});
+}
+
+#[test]
+fn proposal_below_quorum_not_passed() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=3 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		// A single vote out of three doesn't reach the 50% quorum.
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+
+		ProposalPallet::on_initialize(10);
+
+		assert_eq!(Outcomes::get(), vec![(hash, ProposalStatus::QuorumNotReached)]);
+	});
+}
+
+#[test]
+fn abstention_counts_toward_quorum_only() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=3 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		let call =
+			Box::new(RuntimeCall::ProposalPallet(crate::Call::add_community_member { who: 5 }));
+		assert_ok!(ProposalPallet::add_call_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			call
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(2),
+			hash.into(),
+			Vote::Abstain
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(3),
+			hash.into(),
+			Vote::Aye
+		));
+
+		ProposalPallet::on_initialize(10);
+
+		assert_eq!(Outcomes::get(), vec![(hash, ProposalStatus::Approved)]);
+	});
+}
+
+#[test]
+fn silent_members_left_out_once_quorum_reached() {
+	new_test_ext().execute_with(|| {
+		// One aye and one abstention reach the quorum of three members, the silent member
+		// doesn't count.
+		let tally = Tally { ayes: 1, nays: 0, abstains: 1 };
+		assert_eq!(ProposalPallet::decide(&tally, 3), ProposalStatus::Approved);
+
+		let tally = Tally { ayes: 1, nays: 1, abstains: 0 };
+		assert_eq!(ProposalPallet::decide(&tally, 3), ProposalStatus::Rejected);
+
+		let tally = Tally { ayes: 0, nays: 0, abstains: 3 };
+		assert_eq!(ProposalPallet::decide(&tally, 3), ProposalStatus::Rejected);
+
+		let tally = Tally { ayes: 1, nays: 0, abstains: 0 };
+		assert_eq!(ProposalPallet::decide(&tally, 3), ProposalStatus::QuorumNotReached);
+
+		let document = HashType::from(Hashing::hash_of(&42));
+		for who in 1..=4 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		for _ in 0..2 {
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			));
+		}
+		// The quorum of two is reached on the first proposal while member 4 stays silent, the
+		// second proposal gets a single aye.
+		for (who, index, vote) in [(1, 0, Vote::Aye), (2, 0, Vote::Aye), (3, 0, Vote::Nay)]
+			.into_iter()
+			.chain([(1, 1, Vote::Aye)])
+		{
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(who),
+				ProposalRef::Index(index),
+				vote
+			));
+		}
+
+		ProposalPallet::on_initialize(10);
+
+		let outcomes = Outcomes::get();
+		assert!(outcomes.contains(&(ProposalPallet::proposal_id(0), ProposalStatus::Approved)));
+		assert!(
+			outcomes.contains(&(ProposalPallet::proposal_id(1), ProposalStatus::QuorumNotReached))
+		);
+	});
+}
+
+#[test]
+fn add_proposal_with_invalid_payee_shares_fails() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+
+		let payees = vec![(2, Perbill::from_percent(50)), (3, Perbill::from_percent(40))];
+		assert_noop!(
+			ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(1),
+				Vec::new(),
+				document,
+				1000,
+				payees
+			),
+			Error::<Test>::InvalidPayeeShares
+		);
+
+		let payees = vec![(2, Perbill::from_percent(50)), (2, Perbill::from_percent(50))];
+		assert_noop!(
+			ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(1),
+				Vec::new(),
+				document,
+				1000,
+				payees
+			),
+			Error::<Test>::InvalidPayeeShares
+		);
+
+		let payees = vec![(2, Perbill::from_percent(60)), (3, Perbill::from_percent(40))];
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(1),
+			Vec::new(),
+			document,
+			1000,
+			payees.clone()
+		));
+		assert_eq!(ProposalPallet::fund_seeker_accounts(hash), payees);
+	});
+}
+
+#[test]
+fn split_amount_gives_rounding_dust_to_first_payee() {
+	new_test_ext().execute_with(|| {
+		let third = Perbill::from_rational(1u32, 3u32);
+		let payees = vec![(2, third), (3, third), (4, Perbill::one() - third - third)];
+
+		assert_eq!(ProposalPallet::split_amount(&payees, 1000), vec![(2, 334), (3, 333), (4, 333)]);
+	});
+}
+
+#[test]
+fn close_unanimous_proposal_before_deadline() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=2 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		let call =
+			Box::new(RuntimeCall::ProposalPallet(crate::Call::add_community_member { who: 5 }));
+		assert_ok!(ProposalPallet::add_call_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			call
+		));
+		let call_weight = ProposalPallet::call_weight(hash).unwrap();
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+
+		// The second member could still vote against the proposal.
+		assert_noop!(
+			ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight),
+			Error::<Test>::VotingStillOpen
+		);
+
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(2),
+			hash.into(),
+			Vote::Aye
+		));
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight));
+
+		assert_eq!(ProposalPallet::community_members(), vec![1, 2, 5, 9]);
+		assert_noop!(
+			ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight),
+			Error::<Test>::ProposalNotOpen
+		);
+	});
+}
+
+#[test]
+fn close_after_deadline() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=3 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+		assert_eq!(ProposalPallet::voting_deadline(hash), Some(10));
+
+		System::set_block_number(9);
+		assert_noop!(
+			ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()),
+			Error::<Test>::VotingStillOpen
+		);
+
+		System::set_block_number(10);
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()));
+		assert_eq!(Outcomes::get(), vec![(hash, ProposalStatus::QuorumNotReached)]);
+	});
+}
+
+#[test]
+fn proposer_and_payees_cannot_vote() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=3 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+
+		let payees = vec![(2, Perbill::from_percent(50)), (4, Perbill::from_percent(50))];
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(1),
+			Vec::new(),
+			document,
+			1000,
+			payees
+		));
+
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Aye),
+			Error::<Test>::ConflictOfInterest
+		);
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(2), hash.into(), Vote::Aye),
+			Error::<Test>::ConflictOfInterest
+		);
+		// Only the third member is left to vote.
+		assert_eq!(ProposalPallet::electorate(&hash), 1);
+	});
+}
+
+#[test]
+fn recused_member_left_out_of_electorate() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=2 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+
+		let call =
+			Box::new(RuntimeCall::ProposalPallet(crate::Call::add_community_member { who: 5 }));
+		assert_ok!(ProposalPallet::add_call_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			call
+		));
+		let call_weight = ProposalPallet::call_weight(hash).unwrap();
+
+		assert_ok!(ProposalPallet::recuse(RuntimeOrigin::signed(2), hash.into()));
+		assert_eq!(ProposalPallet::recusals(hash), vec![2]);
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(2), hash.into(), Vote::Aye),
+			Error::<Test>::AlreadyRecused
+		);
+
+		// The only member left in the electorate voted, so the proposal can be closed.
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight));
+		assert_eq!(ProposalPallet::community_members(), vec![1, 2, 5, 9]);
+	});
+}
+
+#[test]
+fn secret_votes_committed_and_revealed() {
+	new_test_ext().execute_with(|| {
+		SecretBallots::set(true);
+		System::set_block_number(1);
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		for who in 1..=2 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+
+		let call =
+			Box::new(RuntimeCall::ProposalPallet(crate::Call::add_community_member { who: 5 }));
+		assert_ok!(ProposalPallet::add_call_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			call
+		));
+		let call_weight = ProposalPallet::call_weight(hash).unwrap();
+		// Commit phase until block 6, reveal phase until block 11.
+		assert_eq!(ProposalPallet::reveal_start(hash), Some(6));
+		assert_eq!(ProposalPallet::voting_deadline(hash), Some(11));
+
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Aye),
+			Error::<Test>::SecretBallotsActive
+		);
+
+		let salt = [7u8; 32];
+		let commitment = Hashing::hash_of(&(1u64, hash, Vote::Aye, salt));
+		assert_ok!(ProposalPallet::commit_vote(RuntimeOrigin::signed(1), hash.into(), commitment));
+		// The second member copies the commitment of the first one.
+		assert_ok!(ProposalPallet::commit_vote(RuntimeOrigin::signed(2), hash.into(), commitment));
+		for who in 1..=2 {
+			assert_eq!(Balances::reserved_balance(who), 10);
+		}
+		assert_noop!(
+			ProposalPallet::reveal_vote(RuntimeOrigin::signed(1), hash.into(), Vote::Aye, salt),
+			Error::<Test>::NotRevealPhase
+		);
+
+		System::set_block_number(6);
+		assert_noop!(
+			ProposalPallet::reveal_vote(RuntimeOrigin::signed(1), hash.into(), Vote::Nay, salt),
+			Error::<Test>::InvalidReveal
+		);
+		// A copied commitment is bound to the member who made it.
+		assert_noop!(
+			ProposalPallet::reveal_vote(RuntimeOrigin::signed(2), hash.into(), Vote::Aye, salt),
+			Error::<Test>::InvalidReveal
+		);
+		assert_ok!(ProposalPallet::reveal_vote(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye,
+			salt
+		));
+		// The deposit is returned on reveal.
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 10_000);
+		// The vote of the second member is still secret.
+		assert_noop!(
+			ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight),
+			Error::<Test>::VotingStillOpen
+		);
+
+		// The unrevealed vote counts as an abstention.
+		System::set_block_number(11);
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), call_weight));
+		assert_eq!(
+			ProposalPallet::voting(hash),
+			Some(Votes { ayes: vec![1], nays: vec![], abstains: vec![2] })
+		);
+		// The deposit of the unrevealed vote is forfeited.
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 9_990);
+		assert_eq!(
+			Outcomes::get(),
+			vec![(hash, ProposalStatus::Approved), (hash, ProposalStatus::Executed)]
+		);
+	});
+}
+
+#[test]
+fn only_proposer_amends_open_proposal() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Some(500),
+				None,
+				None
+			),
+			Error::<Test>::NotProposer
+		);
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(9),
+				hash.into(),
+				Some(1000),
+				None,
+				None
+			),
+			Error::<Test>::NoChanges
+		);
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(9),
+				hash.into(),
+				None,
+				Some(vec![(9, Perbill::from_percent(50))]),
+				None
+			),
+			Error::<Test>::InvalidPayeeShares
+		);
+
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()));
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(9),
+				hash.into(),
+				Some(500),
+				None,
+				None
+			),
+			Error::<Test>::ProposalNotOpen
+		);
+	});
+}
+
+#[test]
+fn amendment_resets_votes_and_keeps_history() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		let amended_document = HashType::from(Hashing::hash_of(&43));
+		for who in 1..=2 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_eq!(ProposalPallet::voting_deadline(hash), Some(10));
+
+		System::set_block_number(3);
+		assert_ok!(ProposalPallet::amend_proposal(
+			RuntimeOrigin::signed(9),
+			hash.into(),
+			Some(500),
+			None,
+			Some(amended_document)
+		));
+		assert_eq!(
+			ProposalPallet::proposal_versions(hash),
+			vec![ProposalVersion {
+				version: 0,
+				amount: 1000,
+				payees: vec![(9, Perbill::one())],
+				document,
+				amended_at: 3,
+			}]
+		);
+		assert_eq!(
+			ProposalPallet::voting(hash),
+			Some(Votes { ayes: vec![], nays: vec![], abstains: vec![] })
+		);
+		assert_eq!(ProposalPallet::voting_deadline(hash), None);
+		assert_eq!(ProposalPallet::transfer_time(10, hash), None);
+
+		// The new voting period starts with the next vote.
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+		assert_eq!(ProposalPallet::voting_deadline(hash), Some(13));
+	});
+}
+
+#[test]
+fn amendment_keeps_votes_when_configured() {
+	new_test_ext().execute_with(|| {
+		AmendmentVotePolicy::set(AmendmentPolicy::KeepVotes);
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Aye
+		));
+
+		assert_ok!(ProposalPallet::amend_proposal(
+			RuntimeOrigin::signed(9),
+			hash.into(),
+			None,
+			Some(vec![(9, Perbill::from_percent(60)), (8, Perbill::from_percent(40))]),
+			None
+		));
+		assert_eq!(
+			ProposalPallet::voting(hash),
+			Some(Votes { ayes: vec![1], nays: vec![], abstains: vec![] })
+		);
+		assert_eq!(ProposalPallet::voting_deadline(hash), Some(10));
+		assert_eq!(ProposalPallet::proposal_versions(hash).len(), 1);
+
+		// A kept vote can't come from a payee.
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(9),
+				hash.into(),
+				None,
+				Some(vec![(9, Perbill::from_percent(60)), (1, Perbill::from_percent(40))]),
+				None
+			),
+			Error::<Test>::PayeeAlreadyVoted
+		);
+	});
+}
+
+#[test]
+fn secondary_indexes_follow_proposal_lifecycle() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		for _ in 0..3 {
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			));
+		}
+		assert_eq!(ProposalPallet::proposals_by_proposer(9), vec![0, 1, 2]);
+		assert_eq!(ProposalPallet::proposals_by_beneficiary(9), vec![0, 1, 2]);
+		assert_eq!(ProposalPallet::proposals_by_status(ProposalStatus::Open), vec![0, 1, 2]);
+
+		let payees = vec![(7, Perbill::from_percent(50)), (8, Perbill::from_percent(50))];
+		assert_ok!(ProposalPallet::amend_proposal(
+			RuntimeOrigin::signed(9),
+			ProposalRef::Index(1),
+			None,
+			Some(payees),
+			None
+		));
+		assert_eq!(ProposalPallet::proposals_by_beneficiary(9), vec![0, 2]);
+		assert_eq!(ProposalPallet::proposals_by_beneficiary(7), vec![1]);
+		assert_eq!(ProposalPallet::proposals_by_beneficiary(8), vec![1]);
+
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(0),
+			Vote::Nay
+		));
+		assert_ok!(ProposalPallet::close(
+			RuntimeOrigin::signed(3),
+			ProposalRef::Index(0),
+			Weight::zero()
+		));
+		assert_eq!(ProposalPallet::proposals_by_status(ProposalStatus::Open), vec![1, 2]);
+		assert_eq!(ProposalPallet::proposals_by_status(ProposalStatus::Rejected), vec![0]);
+		assert_eq!(ProposalPallet::proposals_by_proposer(9), vec![0, 1, 2]);
+
+		let page = ProposalPallet::page(ProposalPallet::proposals_by_proposer(9), 1, 1);
+		assert_eq!(page, vec![(1, ProposalPallet::proposal_id(1))]);
+		let page = ProposalPallet::page(ProposalPallet::proposals_by_proposer(9), 2, 10);
+		assert_eq!(page, vec![(2, ProposalPallet::proposal_id(2))]);
+	});
+}
+
+#[test]
+fn proposal_submissions_are_rate_limited() {
+	new_test_ext().execute_with(|| {
+		MaxOpenProposals::set(2);
+		SubmissionGap::set(5);
+		let document = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+
+		let call = RuntimeCall::ProposalPallet(crate::Call::add_proposal {
+			title: Vec::new(),
+			document,
+			amount: 1000,
+			payees: vec![],
+		});
+		let info = DispatchInfo::default();
+		assert_eq!(
+			CheckProposalRate::<Test>::new().validate(&9, &call, &info, 0),
+			Err(InvalidTransaction::Custom(CheckProposalRate::<Test>::SUBMISSION_TOO_SOON).into())
+		);
+		assert_noop!(
+			ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			),
+			Error::<Test>::SubmissionTooSoon
+		);
+
+		System::set_block_number(5);
+		assert_ok!(CheckProposalRate::<Test>::new().validate(&9, &call, &info, 0));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+
+		System::set_block_number(10);
+		assert_eq!(
+			CheckProposalRate::<Test>::new().validate(&9, &call, &info, 0),
+			Err(InvalidTransaction::Custom(CheckProposalRate::<Test>::TOO_MANY_OPEN_PROPOSALS)
+				.into())
+		);
+		// Other calls aren't limited.
+		let call =
+			RuntimeCall::ProposalPallet(crate::Call::recuse { proposal: ProposalRef::Index(0) });
+		assert_ok!(CheckProposalRate::<Test>::new().validate(&9, &call, &info, 0));
+	});
+}
+
+#[test]
+fn rejected_document_resubmitted_after_cooldown() {
+	new_test_ext().execute_with(|| {
+		ResubmissionCooldown::set(20);
+		let document = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(0),
+			Vote::Nay
+		));
+		System::set_block_number(2);
+		assert_ok!(ProposalPallet::close(
+			RuntimeOrigin::signed(3),
+			ProposalRef::Index(0),
+			Weight::zero()
+		));
+		assert_eq!(ProposalPallet::rejected_documents(document), Some(2));
+		assert_eq!(ProposalPallet::open_proposals(9), 0);
+
+		System::set_block_number(21);
+		assert_noop!(
+			ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			),
+			Error::<Test>::ResubmissionCooldown
+		);
+		// Amending another proposal doesn't bypass the cooldown.
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			HashType::from(Hashing::hash_of(&43)),
+			1000,
+			vec![]
+		));
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(9),
+				ProposalRef::Index(1),
+				None,
+				None,
+				Some(document)
+			),
+			Error::<Test>::ResubmissionCooldown
+		);
+		System::set_block_number(22);
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+	});
+}
+
+#[test]
+fn membership_checked_in_transaction_pool() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let info = DispatchInfo::default();
+		let add = RuntimeCall::ProposalPallet(crate::Call::add_proposal {
+			title: Vec::new(),
+			document,
+			amount: 1000,
+			payees: vec![],
+		});
+		let approve = RuntimeCall::ProposalPallet(crate::Call::approve_proposal {
+			proposal: ProposalRef::Index(0),
+			approve: Vote::Aye,
+		});
+		let check = CheckProposalMembership::<Test>::new();
+		let custom = |code| -> TransactionValidity { Err(InvalidTransaction::Custom(code).into()) };
+
+		assert_eq!(
+			check.validate(&9, &add, &info, 0),
+			custom(CheckProposalMembership::<Test>::NOT_COMMUNITY_MEMBER)
+		);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(check.validate(&9, &add, &info, 0));
+		assert_eq!(
+			check.validate(&1, &approve, &info, 0),
+			custom(CheckProposalMembership::<Test>::PROPOSAL_MISSING)
+		);
+
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_eq!(
+			check.validate(&9, &approve, &info, 0),
+			custom(CheckProposalMembership::<Test>::NOT_COMMITTEE_MEMBER)
+		);
+		assert_eq!(check.validate(&1, &approve, &info, 0).unwrap().priority, 0);
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(0),
+			Vote::Aye
+		));
+		assert_eq!(
+			check.validate(&1, &approve, &info, 0),
+			custom(CheckProposalMembership::<Test>::ALREADY_VOTED)
+		);
+
+		// A second member votes close to the deadline at block 10.
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 2));
+		assert_eq!(check.validate(&2, &approve, &info, 0).unwrap().priority, 0);
+		System::set_block_number(8);
+		assert_eq!(
+			check.validate(&2, &approve, &info, 0).unwrap().priority,
+			UrgentVotePriority::get()
+		);
+	});
+}
+
+#[test]
+fn recusals_and_ballots_checked_in_transaction_pool() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let info = DispatchInfo::default();
+		let recuse = |index| {
+			RuntimeCall::ProposalPallet(crate::Call::recuse { proposal: ProposalRef::Index(index) })
+		};
+		let check = CheckProposalMembership::<Test>::new();
+		let custom = |code| -> TransactionValidity { Err(InvalidTransaction::Custom(code).into()) };
+		for who in 1..=3 {
+			assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), who));
+			assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), who));
+		}
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		for _ in 0..2 {
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			));
+		}
+
+		assert_eq!(
+			check.validate(&9, &recuse(0), &info, 0),
+			custom(CheckProposalMembership::<Test>::NOT_COMMITTEE_MEMBER)
+		);
+		assert_eq!(
+			check.validate(&1, &recuse(2), &info, 0),
+			custom(CheckProposalMembership::<Test>::PROPOSAL_MISSING)
+		);
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(0),
+			Vote::Aye
+		));
+		assert_eq!(
+			check.validate(&1, &recuse(0), &info, 0),
+			custom(CheckProposalMembership::<Test>::ALREADY_VOTED)
+		);
+		assert_ok!(check.validate(&2, &recuse(0), &info, 0));
+		assert_ok!(ProposalPallet::recuse(RuntimeOrigin::signed(2), ProposalRef::Index(0)));
+		assert_eq!(
+			check.validate(&2, &recuse(0), &info, 0),
+			custom(CheckProposalMembership::<Test>::NOT_IN_ELECTORATE)
+		);
+
+		// Votes are only valid in the ballot mode of the pallet.
+		let commit = RuntimeCall::ProposalPallet(crate::Call::commit_vote {
+			proposal: ProposalRef::Index(1),
+			commitment: Hashing::hash_of(&(3u64, ProposalPallet::proposal_id(1))),
+		});
+		let approve = RuntimeCall::ProposalPallet(crate::Call::approve_proposal {
+			proposal: ProposalRef::Index(1),
+			approve: Vote::Aye,
+		});
+		assert_eq!(
+			check.validate(&3, &commit, &info, 0),
+			custom(CheckProposalMembership::<Test>::WRONG_BALLOT)
+		);
+		SecretBallots::set(true);
+		assert_eq!(
+			check.validate(&3, &approve, &info, 0),
+			custom(CheckProposalMembership::<Test>::WRONG_BALLOT)
+		);
+		SecretBallots::set(false);
+
+		// A settled proposal can't be recused from.
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(1),
+			Vote::Nay
+		));
+		System::set_block_number(10);
+		assert_ok!(ProposalPallet::close(
+			RuntimeOrigin::signed(3),
+			ProposalRef::Index(1),
+			Weight::zero()
+		));
+		assert_eq!(
+			check.validate(&3, &recuse(1), &info, 0),
+			custom(CheckProposalMembership::<Test>::PROPOSAL_NOT_OPEN)
+		);
+	});
+}
+
+#[test]
+fn committee_votes_are_free_up_to_cap() {
+	new_test_ext().execute_with(|| {
+		MaxFreeVotes::set(1);
+		let document = HashType::from(Hashing::hash_of(&42));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		for _ in 0..3 {
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			));
+		}
+
+		assert!(ProposalPallet::is_free_vote(&1, ProposalRef::Index(0)));
+		assert!(!ProposalPallet::is_free_vote(&9, ProposalRef::Index(0)));
+		let post_info = ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(0),
+			Vote::Aye,
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		// The vote already cast isn't free anymore, nor are votes over the cap.
+		assert!(!ProposalPallet::is_free_vote(&1, ProposalRef::Index(0)));
+		assert!(!ProposalPallet::is_free_vote(&1, ProposalRef::Index(1)));
+		let post_info = ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(1),
+			Vote::Aye,
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::Yes);
+
+		System::set_block_number(1);
+		assert!(ProposalPallet::is_free_vote(&1, ProposalRef::Index(2)));
+		let post_info = ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			ProposalRef::Index(2),
+			Vote::Aye,
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+	});
+}
+
+#[test]
+fn settlement_scheduled_through_scheduler() {
+	new_test_ext().execute_with(|| {
+		UseScheduler::set(true);
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_eq!(Scheduled::get(), vec![(hash, 10)]);
+		assert_eq!(ProposalPallet::transfer_time(10, hash), None);
+
+		// Amending the proposal cancels the settlement until the next vote.
+		assert_ok!(ProposalPallet::amend_proposal(
+			RuntimeOrigin::signed(9),
+			hash.into(),
+			Some(500),
+			None,
+			None
+		));
+		assert_eq!(Scheduled::get(), vec![]);
+		System::set_block_number(2);
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_eq!(Scheduled::get(), vec![(hash, 12)]);
+
+		// The scheduler settles the proposal with the root origin.
+		System::set_block_number(12);
+		assert_noop!(
+			ProposalPallet::settle(RuntimeOrigin::signed(1), hash, Weight::zero()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(ProposalPallet::settle(RuntimeOrigin::root(), hash, Weight::zero()));
+		assert_eq!(Outcomes::get(), vec![(hash, ProposalStatus::Rejected)]);
+	});
+}
+
+#[test]
+fn close_cancels_scheduled_settlement() {
+	new_test_ext().execute_with(|| {
+		UseScheduler::set(true);
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()));
+		assert_eq!(Scheduled::get(), vec![]);
+	});
+}
+
+#[test]
+fn proposal_documents_are_noted_preimages() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let missing = HashType::from(Hashing::hash_of(&43));
+		MissingDocuments::set(vec![missing]);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_noop!(
+			ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				missing,
+				1000,
+				vec![]
+			),
+			Error::<Test>::DocumentMissing
+		);
+		assert_noop!(
+			ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				vec![0; 33],
+				document,
+				1000,
+				vec![]
+			),
+			Error::<Test>::TitleTooLong
+		);
+
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			vec![0; 32],
+			document,
+			1000,
+			vec![]
+		));
+		assert_eq!(RequestedDocuments::get(), vec![document]);
+
+		let hash = ProposalPallet::proposal_id(0);
+		assert_noop!(
+			ProposalPallet::amend_proposal(
+				RuntimeOrigin::signed(9),
+				hash.into(),
+				None,
+				None,
+				Some(missing)
+			),
+			Error::<Test>::DocumentMissing
+		);
+		let amended_document = HashType::from(Hashing::hash_of(&44));
+		assert_ok!(ProposalPallet::amend_proposal(
+			RuntimeOrigin::signed(9),
+			hash.into(),
+			None,
+			None,
+			Some(amended_document)
+		));
+		assert_eq!(RequestedDocuments::get(), vec![amended_document]);
+	});
+}
+
+#[test]
+fn prune_settled_proposal() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_noop!(
+			ProposalPallet::prune(RuntimeOrigin::signed(3), hash.into()),
+			Error::<Test>::ProposalNotSettled
+		);
+
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 2));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 2));
+		assert_ok!(ProposalPallet::recuse(RuntimeOrigin::signed(2), hash.into()));
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_ok!(ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()));
+		assert_ok!(ProposalPallet::prune(RuntimeOrigin::signed(3), ProposalRef::Index(0)));
+		System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::ProposalPruned {
+			proposal_index: 0,
+			proposal_hash: hash,
+		}));
+
+		assert_eq!(ProposalPallet::proposal(hash), None);
+		assert_eq!(ProposalPallet::proposal_hashes(0), None);
+		assert_eq!(ProposalPallet::voting(hash), None);
+		assert!(ProposalPallet::proposals_by_proposer(9).is_empty());
+		assert!(ProposalPallet::proposals_by_status(ProposalStatus::Rejected).is_empty());
+		assert_eq!(RequestedDocuments::get(), vec![]);
+		// Recusals are kept as an audit trail.
+		assert_eq!(ProposalPallet::recusals(hash), vec![2]);
+		// The proposal count is kept, so pruned indices are never reused.
+		assert_eq!(ProposalPallet::proposal_count(), 1);
+	});
+}
+
+#[test]
+fn committee_set_through_change_members() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		ProposalPallet::initialize_members(&[1, 2]);
+		assert_eq!(ProposalPallet::committee_members(), vec![1, 2]);
+
+		ProposalPallet::change_members_sorted(&[3], &[1], &[2, 3]);
+		assert_eq!(ProposalPallet::committee_members(), vec![2, 3]);
+		System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::NewTerm {
+			members: vec![2, 3],
+		}));
+
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Nay),
+			Error::<Test>::MemberIsNotPresentInCommittee
+		);
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(3),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_eq!(ProposalPallet::electorate(&hash), 2);
+	});
+}
+
+#[test]
+fn committee_read_from_provider() {
+	new_test_ext().execute_with(|| {
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		ExternalCommittee::set(Some(vec![5, 6]));
+
+		// The pallet neither changes nor elects an external committee.
+		assert_noop!(
+			ProposalPallet::add_committee_member(RuntimeOrigin::root(), 9),
+			Error::<Test>::ExternalCommittee
+		);
+		assert_noop!(
+			ProposalPallet::submit_candidacy(RuntimeOrigin::signed(9)),
+			Error::<Test>::ExternalCommittee
+		);
+		assert_noop!(
+			ProposalPallet::vote_candidates(RuntimeOrigin::signed(1), vec![9]),
+			Error::<Test>::ExternalCommittee
+		);
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+
+		// The committee kept by the pallet is ignored.
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Nay),
+			Error::<Test>::MemberIsNotPresentInCommittee
+		);
+		assert_eq!(
+			CheckProposalMembership::<Test>::new().validate(
+				&1,
+				&RuntimeCall::ProposalPallet(crate::Call::recuse { proposal: hash.into() }),
+				&DispatchInfo::default(),
+				0
+			),
+			Err(InvalidTransaction::Custom(CheckProposalMembership::<Test>::NOT_COMMITTEE_MEMBER)
+				.into())
+		);
+		assert_eq!(ProposalPallet::electorate(&hash), 2);
+		assert_ok!(ProposalPallet::approve_proposal(
+			RuntimeOrigin::signed(5),
+			hash.into(),
+			Vote::Nay
+		));
+	});
+}
+
+#[test]
+fn pallet_instances_are_independent() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let document = HashType::from(Hashing::hash_of(&42));
+		let hash = ProposalPallet::proposal_id(0);
+		assert_ok!(ProposalPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_noop!(
+			BountyPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1000,
+				vec![]
+			),
+			Error::<Test, Instance1>::MemberIsNotPresentInCommunity
+		);
+
+		assert_ok!(BountyPallet::add_community_member(RuntimeOrigin::root(), 1));
+		assert_ok!(BountyPallet::add_committee_member(RuntimeOrigin::root(), 1));
+		assert_ok!(BountyPallet::add_community_member(RuntimeOrigin::root(), 9));
+		assert_ok!(ProposalPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			1000,
+			vec![]
+		));
+		assert_ok!(BountyPallet::add_proposal(
+			RuntimeOrigin::signed(9),
+			Vec::new(),
+			document,
+			500,
+			vec![]
+		));
+		assert_eq!(BountyPallet::proposal_id(0), hash);
+		assert!(ProposalPallet::committee_members().is_empty());
+
+		// The committee of an instance only votes on its own proposals, within its own period.
+		assert_noop!(
+			ProposalPallet::approve_proposal(RuntimeOrigin::signed(1), hash.into(), Vote::Nay),
+			Error::<Test>::MemberIsNotPresentInCommittee
+		);
+		assert_ok!(BountyPallet::approve_proposal(
+			RuntimeOrigin::signed(1),
+			hash.into(),
+			Vote::Nay
+		));
+		assert_eq!(BountyPallet::voting_deadline(hash), Some(6));
+		assert_eq!(ProposalPallet::voting_deadline(hash), None);
+
+		assert_ok!(BountyPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()));
+		assert_eq!(BountyPallet::proposals_by_status(ProposalStatus::Rejected), vec![0]);
+		assert_eq!(ProposalPallet::proposals_by_status(ProposalStatus::Open), vec![0]);
+	});
+}
+
+#[test]
+fn approved_proposal_paid_from_pot() {
+	ExtBuilder::default()
+		.community(vec![9])
+		.committee(vec![1, 2])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(9), 1_000, None));
+			assert_eq!(Balances::free_balance(POT), 6_000);
+
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![(9, Perbill::from_percent(50)), (10, Perbill::from_percent(50))]
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(2),
+				hash.into(),
+				Vote::Aye
+			));
+
+			// The proposal is settled once the voting period is over.
+			run_to_block(10);
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Open);
+			run_to_block(11);
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(POT), 4_000);
+			assert_eq!(Balances::free_balance(9), 10_000);
+			assert_eq!(Balances::free_balance(10), 11_000);
+			assert_proposal_event(crate::Event::ProposalApproved);
+			assert_proposal_event(crate::Event::FundTransfer);
+		});
+}
+
+#[test]
+fn rejected_proposal_not_paid() {
+	ExtBuilder::default()
+		.community(vec![9])
+		.committee(vec![1, 2])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![]
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(2),
+				hash.into(),
+				Vote::Nay
+			));
+
+			run_to_block(11);
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Rejected);
+			assert_eq!(Balances::free_balance(POT), 5_000);
+			assert_eq!(Balances::free_balance(9), 10_000);
+			assert_proposal_event(crate::Event::ProposalReject);
+			assert_proposal_event(crate::Event::FundTransferDeclined);
+			assert!(!proposal_events().contains(&crate::Event::FundTransfer));
+		});
+}
+
+#[test]
+fn payout_without_pot_fails() {
+	ExtBuilder::default()
+		.community(vec![9])
+		.committee(vec![1])
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![]
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+
+			// The proposal stays approved until a pot is set.
+			run_to_block(11);
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Approved);
+			assert_noop!(
+				ProposalPallet::close(RuntimeOrigin::signed(9), hash.into(), Weight::zero()),
+				Error::<Test>::PotMissing
+			);
+		});
+}
+
+#[test]
+fn split_payout_paid_to_every_payee_or_none() {
+	ExtBuilder::default()
+		.community(vec![9])
+		.committee(vec![1])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			let payees = vec![(9, Perbill::from_percent(99)), (20, Perbill::from_percent(1))];
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				1_000,
+				payees
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+
+			// The 10 due to the second payee are below the existential deposit of its new
+			// account, so the first payee isn't paid either.
+			ExistentialDeposit::set(50);
+			run_to_block(11);
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Approved);
+			assert_proposal_event(crate::Event::PayoutFailed {
+				proposal_hash: hash,
+				error: pallet_balances::Error::<Test>::ExistentialDeposit.into(),
+			});
+			assert_eq!(Balances::free_balance(9), 10_000);
+			assert_eq!(Balances::free_balance(POT), 5_000);
+			assert_noop!(
+				ProposalPallet::close(RuntimeOrigin::signed(9), hash.into(), Weight::zero()),
+				pallet_balances::Error::<Test>::ExistentialDeposit
+			);
+
+			ExistentialDeposit::set(1);
+			assert_ok!(ProposalPallet::close(
+				RuntimeOrigin::signed(9),
+				hash.into(),
+				Weight::zero()
+			));
+			assert_eq!(Balances::free_balance(9), 10_990);
+			assert_eq!(Balances::free_balance(20), 10);
+		});
+}
+
+#[test]
+fn payout_retried_once_pot_is_funded() {
+	ExtBuilder::default()
+		.community(vec![9])
+		.committee(vec![1])
+		.pot(500)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![]
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+
+			// The pot can't pay, the proposal stays approved.
+			run_to_block(11);
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Approved);
+			assert_eq!(Balances::free_balance(POT), 500);
+			assert_eq!(Balances::free_balance(9), 10_000);
+			assert!(!proposal_events().contains(&crate::Event::FundTransfer));
+			assert_noop!(
+				ProposalPallet::close(RuntimeOrigin::signed(3), hash.into(), Weight::zero()),
+				pallet_balances::Error::<Test>::InsufficientBalance
+			);
+
+			assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(1), 2_000, None));
+			assert_ok!(ProposalPallet::close(
+				RuntimeOrigin::signed(3),
+				hash.into(),
+				Weight::zero()
+			));
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(POT), 500);
+			assert_eq!(Balances::free_balance(9), 12_000);
+		});
+}
+
+#[test]
+fn scheduled_settlement_keeps_approval_when_payout_fails() {
+	UseScheduler::set(true);
+	ExtBuilder::default()
+		.community(vec![9])
+		.committee(vec![1])
+		.pot(500)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![]
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+			assert_eq!(Scheduled::get(), vec![(hash, 11)]);
+
+			// The scheduled task settles the proposal even though the pot can't pay it.
+			System::set_block_number(11);
+			assert_ok!(ProposalPallet::settle(RuntimeOrigin::root(), hash, Weight::zero()));
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Approved);
+			assert_eq!(Outcomes::get(), vec![(hash, ProposalStatus::Approved)]);
+			assert_proposal_event(crate::Event::PayoutFailed {
+				proposal_hash: hash,
+				error: pallet_balances::Error::<Test>::InsufficientBalance.into(),
+			});
+
+			assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(9), 2_000, None));
+			assert_ok!(ProposalPallet::close(
+				RuntimeOrigin::signed(3),
+				hash.into(),
+				Weight::zero()
+			));
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Paid);
+		});
+}
+
+#[test]
+fn donations_recorded_per_donor() {
+	ExtBuilder::default().community(vec![1, 2]).pot(5_000).build().execute_with(|| {
+		run_to_block(1);
+		let memo = HashType::from(Hashing::hash_of(&"for the grants"));
+		assert_noop!(
+			ProposalPallet::fund_pot_account(RuntimeOrigin::signed(3), 100, None),
+			Error::<Test>::MemberIsNotPresentInCommunity
+		);
+
+		assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(1), 100, Some(memo)));
+		assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(2), 300, None));
+		run_to_block(2);
+		assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(1), 250, None));
+		System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::Donated {
+			donor: 1,
+			amount: 250,
+			memo: None,
+			total: 350,
+		}));
+
+		// The funds come from the signer.
+		assert_eq!(Balances::free_balance(1), 9_650);
+		assert_eq!(Balances::free_balance(POT), 5_650);
+		assert_eq!(
+			ProposalPallet::donations(1),
+			vec![
+				Donation { amount: 100, memo: Some(memo), block: 1 },
+				Donation { amount: 250, memo: None, block: 2 }
+			]
+		);
+		assert_eq!(ProposalPallet::donated(1), 350);
+		assert_eq!(ProposalPallet::donated(2), 300);
+		assert_eq!(ProposalPallet::top_donors(10), vec![(1, 350), (2, 300)]);
+		assert_eq!(ProposalPallet::top_donors(1), vec![(1, 350)]);
+	});
+}
+
+#[test]
+fn donation_without_pot_fails() {
+	ExtBuilder::default().community(vec![1]).build().execute_with(|| {
+		assert_noop!(
+			ProposalPallet::fund_pot_account(RuntimeOrigin::signed(1), 100, None),
+			Error::<Test>::PotMissing
+		);
+	});
+}
+
+#[test]
+fn escrowed_donations_applied_first_at_payout() {
+	ExtBuilder::default()
+		.community(vec![3, 9])
+		.committee(vec![1, 2])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![]
+			));
+
+			assert_noop!(
+				ProposalPallet::donate_to_proposal(RuntimeOrigin::signed(5), hash.into(), 500),
+				Error::<Test>::MemberIsNotPresentInCommunity
+			);
+			assert_ok!(ProposalPallet::donate_to_proposal(
+				RuntimeOrigin::signed(3),
+				ProposalRef::Index(0),
+				500
+			));
+			System::assert_last_event(RuntimeEvent::ProposalPallet(
+				crate::Event::DonatedToProposal {
+					proposal_hash: hash,
+					donor: 3,
+					amount: 500,
+					escrowed: 500,
+				},
+			));
+			assert_noop!(
+				ProposalPallet::donate_to_proposal(RuntimeOrigin::signed(3), hash.into(), 1_501),
+				Error::<Test>::DonationExceedsGap
+			);
+			assert_noop!(
+				ProposalPallet::amend_proposal(
+					RuntimeOrigin::signed(9),
+					hash.into(),
+					Some(400),
+					None,
+					None
+				),
+				Error::<Test>::AmountBelowEscrow
+			);
+			let proposal = ProposalPallet::proposal(hash).unwrap();
+			assert_eq!((proposal.escrowed, proposal.gap), (500, 1_500));
+			assert_eq!(ProposalPallet::escrows(hash, 3), 500);
+			assert_eq!(Balances::reserved_balance(3), 500);
+
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(2),
+				hash.into(),
+				Vote::Aye
+			));
+			run_to_block(11);
+
+			// The pot only paid the gap.
+			assert_eq!(ProposalPallet::proposal(hash).unwrap().status, ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(9), 12_000);
+			assert_eq!(Balances::free_balance(POT), 3_500);
+			assert_eq!(Balances::total_balance(&3), 9_500);
+			assert_eq!(ProposalPallet::escrows(hash, 3), 0);
+			assert_proposal_event(crate::Event::EscrowApplied { proposal_hash: hash, amount: 500 });
+		});
+}
+
+#[test]
+fn donation_withdrawn_until_first_vote() {
+	ExtBuilder::default()
+		.community(vec![3, 9])
+		.committee(vec![1, 2])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let hash = ProposalPallet::proposal_id(0);
+			assert_ok!(ProposalPallet::add_proposal(
+				RuntimeOrigin::signed(9),
+				Vec::new(),
+				document,
+				2_000,
+				vec![]
+			));
+			assert_noop!(
+				ProposalPallet::donate_to_proposal(RuntimeOrigin::signed(3), hash.into(), 0),
+				Error::<Test>::ZeroDonation
+			);
+			assert_ok!(ProposalPallet::donate_to_proposal(
+				RuntimeOrigin::signed(3),
+				hash.into(),
+				500
+			));
+			assert_noop!(
+				ProposalPallet::withdraw_donation(RuntimeOrigin::signed(9), hash.into()),
+				Error::<Test>::NoDonation
+			);
+
+			assert_ok!(ProposalPallet::withdraw_donation(RuntimeOrigin::signed(3), hash.into()));
+			System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::EscrowRefunded {
+				proposal_hash: hash,
+				donor: 3,
+				amount: 500,
+			}));
+			let proposal = ProposalPallet::proposal(hash).unwrap();
+			assert_eq!((proposal.escrowed, proposal.gap), (0, 2_000));
+			assert_eq!(Balances::reserved_balance(3), 0);
+
+			// Once the voting started, the donation stays until the proposal is settled.
+			assert_ok!(ProposalPallet::donate_to_proposal(
+				RuntimeOrigin::signed(3),
+				hash.into(),
+				500
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				hash.into(),
+				Vote::Aye
+			));
+			assert_noop!(
+				ProposalPallet::withdraw_donation(RuntimeOrigin::signed(3), hash.into()),
+				Error::<Test>::DonationLocked
+			);
+			assert_eq!(Balances::reserved_balance(3), 500);
+		});
+}
+
+#[test]
+fn escrowed_donations_refunded_when_not_approved() {
+	ExtBuilder::default()
+		.community(vec![4, 5, 8, 9])
+		.committee(vec![1, 2, 3])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let rejected = ProposalPallet::proposal_id(0);
+			let expired = ProposalPallet::proposal_id(1);
+			for who in [9, 8] {
+				assert_ok!(ProposalPallet::add_proposal(
+					RuntimeOrigin::signed(who),
+					Vec::new(),
+					document,
+					2_000,
+					vec![]
+				));
+			}
+			assert_ok!(ProposalPallet::donate_to_proposal(
+				RuntimeOrigin::signed(4),
+				rejected.into(),
+				400
+			));
+			assert_ok!(ProposalPallet::donate_to_proposal(
+				RuntimeOrigin::signed(5),
+				rejected.into(),
+				100
+			));
+			assert_ok!(ProposalPallet::donate_to_proposal(
+				RuntimeOrigin::signed(4),
+				expired.into(),
+				300
+			));
+			assert_eq!(Balances::reserved_balance(4), 700);
+
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				rejected.into(),
+				Vote::Nay
+			));
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(2),
+				rejected.into(),
+				Vote::Aye
+			));
+			// A single vote out of three doesn't reach the quorum.
+			run_to_block(2);
+			assert_ok!(ProposalPallet::approve_proposal(
+				RuntimeOrigin::signed(1),
+				expired.into(),
+				Vote::Aye
+			));
+			run_to_block(12);
+
+			let proposal = ProposalPallet::proposal(rejected).unwrap();
+			assert_eq!(proposal.status, ProposalStatus::Rejected);
+			assert_eq!((proposal.escrowed, proposal.gap), (0, 2_000));
+			let proposal = ProposalPallet::proposal(expired).unwrap();
+			assert_eq!(proposal.status, ProposalStatus::QuorumNotReached);
+			assert_eq!((proposal.escrowed, proposal.gap), (0, 2_000));
+
+			assert_eq!(Balances::free_balance(4), 10_000);
+			assert_eq!(Balances::free_balance(5), 10_000);
+			assert_eq!(Balances::reserved_balance(4), 0);
+			assert_eq!(Balances::free_balance(POT), 5_000);
+			assert_proposal_event(crate::Event::EscrowRefunded {
+				proposal_hash: rejected,
+				donor: 5,
+				amount: 100,
+			});
+			assert_proposal_event(crate::Event::EscrowRefunded {
+				proposal_hash: expired,
+				donor: 4,
+				amount: 300,
+			});
+			assert_noop!(
+				ProposalPallet::donate_to_proposal(RuntimeOrigin::signed(4), rejected.into(), 100),
+				Error::<Test>::ProposalNotOpen
+			);
+		});
+}
+
+#[test]
+fn quadratic_matches_favor_broad_support() {
+	assert_eq!(ProposalPallet::fixed_sqrt(100), 10_000);
+	assert_eq!(ProposalPallet::fixed_sqrt(2), 1_414);
+	// Four contributions of 100 weigh as much as a single one of 1600.
+	let roots = [4 * ProposalPallet::fixed_sqrt(100), ProposalPallet::fixed_sqrt(1_600)];
+	assert_eq!(ProposalPallet::quadratic_matches(&roots, 1_000), vec![500, 500]);
+	let roots = [3 * ProposalPallet::fixed_sqrt(100), ProposalPallet::fixed_sqrt(100)];
+	assert_eq!(ProposalPallet::quadratic_matches(&roots, 1_000), vec![900, 100]);
+	// The rounding dust stays in the pot.
+	assert_eq!(ProposalPallet::quadratic_matches(&[1, 1, 1], 100), vec![33, 33, 33]);
+	assert_eq!(ProposalPallet::quadratic_matches(&[0, 0], 1_000), vec![0, 0]);
+}
+
+#[test]
+fn funding_round_matches_contributions() {
+	// Approved proposals wait for a budget round, which comes after the funding round.
+	BudgetPeriod::set(100);
+	ExtBuilder::default()
+		.community(vec![3, 4, 5, 6, 8, 9])
+		.committee(vec![1])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let broad = ProposalPallet::proposal_id(0);
+			let narrow = ProposalPallet::proposal_id(1);
+			let open = ProposalPallet::proposal_id(2);
+			for (who, amount) in [(9, 2_000), (8, 2_000), (6, 1_000)] {
+				assert_ok!(ProposalPallet::add_proposal(
+					RuntimeOrigin::signed(who),
+					Vec::new(),
+					document,
+					amount,
+					vec![]
+				));
+			}
+			for hash in [broad, narrow] {
+				assert_ok!(ProposalPallet::approve_proposal(
+					RuntimeOrigin::signed(1),
+					hash.into(),
+					Vote::Aye
+				));
+				assert_ok!(ProposalPallet::close(
+					RuntimeOrigin::signed(1),
+					hash.into(),
+					Weight::zero()
+				));
+			}
+
+			assert_noop!(
+				ProposalPallet::open_round(RuntimeOrigin::signed(3), 2, 10, 1_000),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				ProposalPallet::open_round(RuntimeOrigin::root(), 10, 10, 1_000),
+				Error::<Test>::InvalidRoundPeriod
+			);
+			assert_noop!(
+				ProposalPallet::open_round(RuntimeOrigin::root(), 2, 10, 5_001),
+				pallet_balances::Error::<Test>::InsufficientBalance
+			);
+			assert_ok!(ProposalPallet::open_round(RuntimeOrigin::root(), 2, 10, 1_000));
+			assert_eq!(Balances::reserved_balance(POT), 1_000);
+			assert_noop!(
+				ProposalPallet::contribute(RuntimeOrigin::signed(3), 0, broad.into(), 100),
+				Error::<Test>::RoundNotActive
+			);
+
+			run_to_block(2);
+			assert_noop!(
+				ProposalPallet::contribute(RuntimeOrigin::signed(7), 0, broad.into(), 100),
+				Error::<Test>::MemberIsNotPresentInCommunity
+			);
+			for who in 3..=6 {
+				assert_ok!(ProposalPallet::contribute(
+					RuntimeOrigin::signed(who),
+					0,
+					broad.into(),
+					100
+				));
+			}
+			assert_ok!(ProposalPallet::contribute(
+				RuntimeOrigin::signed(3),
+				0,
+				narrow.into(),
+				1_600
+			));
+			assert_ok!(ProposalPallet::contribute(RuntimeOrigin::signed(9), 0, open.into(), 100));
+			assert_eq!(Balances::reserved_balance(3), 1_700);
+			assert_noop!(
+				ProposalPallet::close_round(RuntimeOrigin::signed(1), 0),
+				Error::<Test>::RoundNotEnded
+			);
+
+			run_to_block(10);
+			assert_noop!(
+				ProposalPallet::contribute(RuntimeOrigin::signed(4), 0, broad.into(), 100),
+				Error::<Test>::RoundNotActive
+			);
+			assert_ok!(ProposalPallet::close_round(RuntimeOrigin::signed(1), 0));
+
+			// Both approved proposals get half of the pool, in addition to their contributions,
+			// but the narrow one only misses 400 after its contributions.
+			assert_proposal_event(crate::Event::RoundMatched {
+				round: 0,
+				proposal_hash: broad,
+				contributors: 4,
+				contributed: 400,
+				matched: 500,
+			});
+			assert_proposal_event(crate::Event::RoundMatched {
+				round: 0,
+				proposal_hash: narrow,
+				contributors: 1,
+				contributed: 1_600,
+				matched: 400,
+			});
+			System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::RoundClosed {
+				round: 0,
+				matched: 900,
+			}));
+			assert_eq!(Balances::free_balance(9), 10_900);
+			assert_eq!(Balances::free_balance(8), 12_000);
+			assert_eq!(Balances::total_balance(&3), 8_300);
+			// The contribution to the open proposal is refunded.
+			assert_eq!(Balances::reserved_balance(9), 0);
+			assert_eq!(Balances::reserved_balance(POT), 0);
+			assert_eq!(Balances::free_balance(POT), 4_100);
+			assert_eq!(ProposalPallet::round(0), None);
+			assert_noop!(
+				ProposalPallet::close_round(RuntimeOrigin::signed(1), 0),
+				Error::<Test>::RoundMissing
+			);
+
+			// The narrow proposal is fully funded, the broad one still misses 1_100.
+			let proposal = ProposalPallet::proposal(narrow).unwrap();
+			assert_eq!((proposal.status, proposal.gap), (ProposalStatus::Paid, 0));
+			let proposal = ProposalPallet::proposal(broad).unwrap();
+			assert_eq!((proposal.status, proposal.gap), (ProposalStatus::Approved, 1_100));
+
+			// The budget round only pays what the broad proposal still misses.
+			run_to_block(100);
+			assert_eq!(ProposalPallet::proposal(broad).unwrap().status, ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(9), 12_000);
+			assert_eq!(Balances::free_balance(POT), 3_000);
+		});
+}
+
+#[test]
+fn failed_round_payout_refunds_its_contributions() {
+	BudgetPeriod::set(100);
+	ExtBuilder::default()
+		.community(vec![3, 4, 8, 9])
+		.committee(vec![1])
+		.pot(5_000)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			let paid = ProposalPallet::proposal_id(0);
+			let failed = ProposalPallet::proposal_id(1);
+			let payees = vec![(8, Perbill::from_percent(99)), (20, Perbill::from_percent(1))];
+			for (who, amount, payees) in [(9, 2_000, vec![]), (8, 1_000, payees)] {
+				assert_ok!(ProposalPallet::add_proposal(
+					RuntimeOrigin::signed(who),
+					Vec::new(),
+					document,
+					amount,
+					payees
+				));
+			}
+			for hash in [paid, failed] {
+				assert_ok!(ProposalPallet::approve_proposal(
+					RuntimeOrigin::signed(1),
+					hash.into(),
+					Vote::Aye
+				));
+				assert_ok!(ProposalPallet::close(
+					RuntimeOrigin::signed(1),
+					hash.into(),
+					Weight::zero()
+				));
+			}
+			assert_ok!(ProposalPallet::open_round(RuntimeOrigin::root(), 2, 10, 1_000));
+			run_to_block(2);
+			assert_ok!(ProposalPallet::contribute(RuntimeOrigin::signed(3), 0, paid.into(), 100));
+			assert_ok!(ProposalPallet::contribute(RuntimeOrigin::signed(4), 0, failed.into(), 100));
+
+			// The 6 due to the second payee of the failed proposal would be below the existential
+			// deposit of its new account.
+			ExistentialDeposit::set(50);
+			run_to_block(10);
+			assert_ok!(ProposalPallet::close_round(RuntimeOrigin::signed(1), 0));
+
+			assert_proposal_event(crate::Event::RoundPayoutFailed {
+				round: 0,
+				proposal_hash: failed,
+				error: pallet_balances::Error::<Test>::ExistentialDeposit.into(),
+			});
+			System::assert_last_event(RuntimeEvent::ProposalPallet(crate::Event::RoundClosed {
+				round: 0,
+				matched: 500,
+			}));
+			assert_eq!(Balances::free_balance(9), 10_600);
+			assert_eq!(Balances::free_balance(4), 10_000);
+			assert_eq!(Balances::reserved_balance(4), 0);
+			assert_eq!(Balances::free_balance(POT), 4_500);
+			assert_eq!(ProposalPallet::proposal(failed).unwrap().gap, 1_000);
+			assert_eq!(ProposalPallet::round(0), None);
+		});
+}
+
+#[test]
+fn budget_allocation_fits_the_budget() {
+	assert_eq!(ProposalPallet::allocate_greedy(&[600, 500, 300], 1_000), vec![true, false, true]);
+	assert_eq!(ProposalPallet::allocate_greedy(&[600, 500, 500], 1_000), vec![true, false, false]);
+	assert_eq!(ProposalPallet::allocate_knapsack(&[600, 500, 500], 1_000), vec![false, true, true]);
+	// Costs are exact, whatever their share of the budget.
+	assert_eq!(ProposalPallet::allocate_knapsack(&[501, 499], 1_000), vec![true, true]);
+	assert_eq!(
+		ProposalPallet::allocate_knapsack(&[3, 7, 5, 4], 12),
+		vec![false, true, true, false]
+	);
+	// Fully escrowed proposals cost nothing to the pot.
+	assert_eq!(ProposalPallet::allocate_knapsack(&[0, 2_000], 1_000), vec![true, false]);
+	assert_eq!(ProposalPallet::allocate_knapsack(&[1, 1], 0), vec![false, false]);
+	// The knapsack explores at most every selection, or every amount of the budget.
+	assert_eq!(ProposalPallet::knapsack_weight(3, 1_000), Weight::from_ref_time(3 * 8 * 100_000));
+	assert_eq!(ProposalPallet::knapsack_weight(3, 4), Weight::from_ref_time(3 * 5 * 100_000));
+}
+
+#[test]
+fn budget_round_defers_proposals_past_the_limit() {
+	BudgetPeriod::set(15);
+	ExtBuilder::default()
+		.community(vec![6, 7, 8, 9])
+		.committee(vec![1])
+		.pot(10_001)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			for who in 6..=9 {
+				assert_ok!(ProposalPallet::add_proposal(
+					RuntimeOrigin::signed(who),
+					Vec::new(),
+					document,
+					100,
+					vec![]
+				));
+			}
+			let hashes: Vec<_> = (0..4).map(ProposalPallet::proposal_id).collect();
+			for hash in hashes.iter() {
+				assert_ok!(ProposalPallet::approve_proposal(
+					RuntimeOrigin::signed(1),
+					(*hash).into(),
+					Vote::Aye
+				));
+				assert_ok!(ProposalPallet::close(
+					RuntimeOrigin::signed(1),
+					(*hash).into(),
+					Weight::zero()
+				));
+			}
+
+			// A round considers three proposals, the last one is deferred although the budget
+			// could pay it.
+			run_to_block(15);
+			let status = |index: usize| ProposalPallet::proposal(hashes[index]).unwrap().status;
+			assert_eq!(
+				(0..4).map(status).collect::<Vec<_>>(),
+				vec![
+					ProposalStatus::Paid,
+					ProposalStatus::Paid,
+					ProposalStatus::Paid,
+					ProposalStatus::Deferred
+				]
+			);
+			assert_proposal_event(crate::Event::ProposalDeferred { proposal_hash: hashes[3] });
+			System::assert_last_event(RuntimeEvent::ProposalPallet(
+				crate::Event::BudgetRoundClosed {
+					budget: 10_000,
+					spent: 300,
+					funded: 3,
+					deferred: 1,
+				},
+			));
+
+			run_to_block(30);
+			assert_eq!(status(3), ProposalStatus::Paid);
+		});
+}
+
+#[test]
+fn budget_round_defers_unfunded_proposals() {
+	BudgetPeriod::set(15);
+	ExtBuilder::default()
+		.community(vec![7, 8, 9])
+		.committee(vec![1, 2, 3])
+		.pot(1_001)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			for (who, amount) in [(7, 600), (8, 500), (9, 300)] {
+				assert_ok!(ProposalPallet::add_proposal(
+					RuntimeOrigin::signed(who),
+					Vec::new(),
+					document,
+					amount,
+					vec![]
+				));
+			}
+			// The second proposal gets fewer ayes.
+			for (index, vote) in [(0, Vote::Aye), (1, Vote::Abstain), (2, Vote::Aye)] {
+				for (who, vote) in [(1, Vote::Aye), (2, Vote::Aye), (3, vote)] {
+					assert_ok!(ProposalPallet::approve_proposal(
+						RuntimeOrigin::signed(who),
+						ProposalRef::Index(index),
+						vote
+					));
+				}
+			}
+			let hashes: Vec<_> = (0..3).map(ProposalPallet::proposal_id).collect();
+			// All three proposals are settled at the same block.
+			for hash in hashes.iter() {
+				assert_eq!(ProposalPallet::transfer_time(11, hash), Some(()));
+			}
+
+			// Approved proposals wait for the budget round.
+			run_to_block(14);
+			assert_eq!(TransferTime::<Test>::iter_prefix(11).count(), 0);
+			for hash in hashes.iter() {
+				assert_eq!(
+					ProposalPallet::proposal(hash).unwrap().status,
+					ProposalStatus::Approved
+				);
+			}
+			assert_noop!(
+				ProposalPallet::close(RuntimeOrigin::signed(7), hashes[0].into(), Weight::zero()),
+				Error::<Test>::ProposalNotOpen
+			);
+
+			run_to_block(15);
+			let status = |index: usize| ProposalPallet::proposal(hashes[index]).unwrap().status;
+			assert_eq!(status(0), ProposalStatus::Paid);
+			assert_eq!(status(1), ProposalStatus::Deferred);
+			assert_eq!(status(2), ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(POT), 101);
+			assert_proposal_event(crate::Event::ProposalDeferred { proposal_hash: hashes[1] });
+			System::assert_last_event(RuntimeEvent::ProposalPallet(
+				crate::Event::BudgetRoundClosed {
+					budget: 1_000,
+					spent: 900,
+					funded: 2,
+					deferred: 1,
+				},
+			));
+
+			// The deferred proposal rolls over to the next round.
+			assert_ok!(ProposalPallet::fund_pot_account(RuntimeOrigin::signed(9), 500, None));
+			run_to_block(30);
+			assert_eq!(status(1), ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(8), 10_500);
+			assert_eq!(Balances::free_balance(POT), 101);
+		});
+}
+
+#[test]
+fn committee_ranking_orders_budget_round() {
+	BudgetPeriod::set(15);
+	BudgetRankingPolicy::set(BudgetRanking::CommitteeRanking);
+	ExtBuilder::default()
+		.community(vec![7, 8, 9])
+		.committee(vec![1, 2, 3])
+		.pot(1_001)
+		.build()
+		.execute_with(|| {
+			run_to_block(1);
+			let document = HashType::from(Hashing::hash_of(&42));
+			for (who, amount) in [(7, 600), (8, 500), (9, 500)] {
+				assert_ok!(ProposalPallet::add_proposal(
+					RuntimeOrigin::signed(who),
+					Vec::new(),
+					document,
+					amount,
+					vec![]
+				));
+			}
+			for index in 0..3 {
+				for who in 1..=3 {
+					assert_ok!(ProposalPallet::approve_proposal(
+						RuntimeOrigin::signed(who),
+						ProposalRef::Index(index),
+						Vote::Aye
+					));
+				}
+				run_to_block(System::block_number() + 1);
+			}
+			// Only approved proposals can be ranked.
+			assert_noop!(
+				ProposalPallet::rank_proposals(RuntimeOrigin::root(), vec![2, 1]),
+				Error::<Test>::InvalidRanking
+			);
+
+			run_to_block(14);
+			assert_noop!(
+				ProposalPallet::rank_proposals(RuntimeOrigin::signed(1), vec![2, 1]),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				ProposalPallet::rank_proposals(RuntimeOrigin::root(), vec![2, 1, 0, 3]),
+				Error::<Test>::TooManyRanked
+			);
+			assert_noop!(
+				ProposalPallet::rank_proposals(RuntimeOrigin::root(), vec![2, 2]),
+				Error::<Test>::InvalidRanking
+			);
+			assert_noop!(
+				ProposalPallet::rank_proposals(RuntimeOrigin::root(), vec![2, 7]),
+				Error::<Test>::InvalidRanking
+			);
+			// Without a ranking, the first proposal would be funded alone.
+			assert_eq!(
+				ProposalPallet::allocate_greedy(&[600, 500, 500], 1_000),
+				vec![true, false, false]
+			);
+			assert_ok!(ProposalPallet::rank_proposals(RuntimeOrigin::root(), vec![2, 1]));
+			assert_eq!(
+				ProposalPallet::ranked_proposals()
+					.into_iter()
+					.map(|(hash, _)| hash)
+					.collect::<Vec<_>>(),
+				vec![2, 1, 0].into_iter().map(ProposalPallet::proposal_id).collect::<Vec<_>>()
+			);
+
+			// The two ranked proposals spend the whole budget.
+			run_to_block(15);
+			let status = |index| {
+				ProposalPallet::proposal(ProposalPallet::proposal_id(index)).unwrap().status
+			};
+			assert_eq!(status(0), ProposalStatus::Deferred);
+			assert_eq!(status(1), ProposalStatus::Paid);
+			assert_eq!(status(2), ProposalStatus::Paid);
+			assert_eq!(Balances::free_balance(POT), 1);
+		});
+}
@@ -15,9 +15,10 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One,
+		StaticLookup, Verify,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
 use sp_std::prelude::*;
@@ -29,7 +30,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, EitherOfDiverse, EqualPrivilegeOnly,
+		KeyOwnerProofSystem, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -106,10 +108,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -276,6 +278,71 @@ impl pallet_sudo::Config for Runtime {
 	type RuntimeCall = RuntimeCall;
 }
 
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+	pub const MaxScheduledPerBlock: u32 = 50;
+	pub const PreimageBaseDeposit: Balance = 100 * EXISTENTIAL_DEPOSIT;
+	pub const PreimageByteDeposit: Balance = EXISTENTIAL_DEPOSIT / 100;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = frame_system::EnsureRoot<AccountId>;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+type EnsureRootOrHalfCouncil = EitherOfDiverse<
+	frame_system::EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
+>;
+
+impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddOrigin = EnsureRootOrHalfCouncil;
+	type RemoveOrigin = EnsureRootOrHalfCouncil;
+	type SwapOrigin = EnsureRootOrHalfCouncil;
+	type ResetOrigin = EnsureRootOrHalfCouncil;
+	type PrimeOrigin = EnsureRootOrHalfCouncil;
+	type MembershipInitialized = Council;
+	type MembershipChanged = Council;
+	type MaxMembers = CouncilMaxMembers;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
@@ -286,13 +353,162 @@ parameter_types! {
 	// For seven days it would be 100800 blocks.
 	// We are using 10 for testing.
 	pub const TimeDuration: u32 = 10;
+	// Bounties are smaller and decided faster than grants.
+	pub const BountyTimeDuration: u32 = 5;
+	// Bond reserved from a candidate until the committee election is counted.
+	pub const CandidacyBond: Balance = 1_000 * EXISTENTIAL_DEPOSIT;
+	// The committee is elected again every seven days.
+	pub const TermDuration: u32 = 7 * DAYS;
+	pub const DesiredMembers: u32 = 5;
+	// Deposit reserved from an applicant until the membership application is decided.
+	pub const ApplicationDeposit: Balance = 100 * EXISTENTIAL_DEPOSIT;
+	pub const VouchThreshold: u32 = 3;
+	// Maximum encoded length of a call carried by a proposal.
+	pub const MaxCallLength: u32 = 16 * 1024;
+	// Maximum number of contributors sharing the funds of a proposal.
+	pub const MaxPayees: u32 = 16;
+	// Every committee member has to vote, abstentions included, for a proposal to pass.
+	pub const Quorum: Perbill = Perbill::from_percent(100);
+	// Votes are public, set to true to commit and reveal secret votes instead.
+	pub const SecretBallots: bool = false;
+	pub const CommitDuration: u32 = 5 * DAYS;
+	pub const RevealDuration: u32 = 2 * DAYS;
+	// Deposit slashed when a committed vote isn't revealed.
+	pub const CommitDeposit: Balance = 10 * EXISTENTIAL_DEPOSIT;
+	// Amended proposals are voted on again.
+	pub const AmendmentVotePolicy: pallet_proposal::AmendmentPolicy =
+		pallet_proposal::AmendmentPolicy::ResetVotes;
+	// At most 5 open proposals per member, submitted at least an hour apart.
+	pub const MaxOpenProposals: u32 = 5;
+	pub const SubmissionGap: u32 = HOURS;
+	// Rejected documents can be resubmitted after a week.
+	pub const ResubmissionCooldown: u32 = 7 * DAYS;
+	// Committee votes in the last hour of a voting phase jump ahead in the transaction pool.
+	pub const UrgentVoteWindow: u32 = HOURS;
+	pub const UrgentVotePriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	// Committee members vote for free, up to 10 votes in a block.
+	pub const MaxFreeVotes: u32 = 10;
+	// Approved grants are paid by weekly budget rounds, in the order ranked by the council.
+	pub const GrantsBudgetPeriod: u32 = 7 * DAYS;
+	pub const GrantsBudgetRanking: pallet_proposal::BudgetRanking =
+		pallet_proposal::BudgetRanking::CommitteeRanking;
+	pub const GrantsBudgetAllocation: pallet_proposal::BudgetAllocation =
+		pallet_proposal::BudgetAllocation::Greedy;
+	// A budget round funds at most 16 proposals, the others wait for the next one.
+	pub const MaxRoundProposals: u32 = 16;
+	// Approved bounties are paid right away.
+	pub const BountiesBudgetRanking: pallet_proposal::BudgetRanking =
+		pallet_proposal::BudgetRanking::ApprovalScore;
+	pub const BountiesBudgetAllocation: pallet_proposal::BudgetAllocation =
+		pallet_proposal::BudgetAllocation::Greedy;
+}
+
+/// The Grants program, decided by the council.
+pub type GrantsInstance = pallet_proposal::Instance1;
+/// The Bounties program, decided by a committee elected by its own community.
+pub type BountiesInstance = pallet_proposal::Instance2;
+
+/// The funding programs served by the runtime APIs of the proposal pallet.
+#[derive(codec::Encode, codec::Decode, Clone, Copy, PartialEq, Eq, scale_info::TypeInfo)]
+pub enum FundingProgram {
+	Grants,
+	Bounties,
 }
 
-/// Configure the pallet-proposal in pallets/proposal.
-impl pallet_proposal::Config for Runtime {
+/// Configure the pallet-proposal in pallets/proposal for the Grants program.
+impl pallet_proposal::Config<GrantsInstance> for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type TimeDuration = TimeDuration;
+	type CandidacyBond = CandidacyBond;
+	// The council is not elected by the pallet.
+	type TermDuration = ConstU32<0>;
+	type DesiredMembers = DesiredMembers;
+	type ApplicationDeposit = ApplicationDeposit;
+	type VouchThreshold = VouchThreshold;
+	// Members are admitted by sudo or by an approved call proposal.
+	type AdmissionOrigin = EitherOfDiverse<
+		frame_system::EnsureRoot<AccountId>,
+		pallet_proposal::EnsureProposal<Runtime, GrantsInstance>,
+	>;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type MaxCallLength = MaxCallLength;
+	type OnProposalOutcome = ();
+	type MaxPayees = MaxPayees;
+	type Quorum = Quorum;
+	type SecretBallots = SecretBallots;
+	type CommitDuration = CommitDuration;
+	type RevealDuration = RevealDuration;
+	type CommitDeposit = CommitDeposit;
+	type AmendmentPolicy = AmendmentVotePolicy;
+	type MaxOpenProposals = MaxOpenProposals;
+	type SubmissionGap = SubmissionGap;
+	type ResubmissionCooldown = ResubmissionCooldown;
+	type UrgentVoteWindow = UrgentVoteWindow;
+	type UrgentVotePriority = UrgentVotePriority;
+	type MaxFreeVotes = MaxFreeVotes;
+	// Proposals are settled by named tasks of the scheduler, set to `()` to settle them through
+	// the `TransferTime` hook of the pallet instead.
+	type Settlement =
+		pallet_proposal::SchedulerSettlement<Runtime, Scheduler, OriginCaller, GrantsInstance>;
+	// Proposal documents are noted as preimages, with a deposit for their size.
+	type Preimages = Preimage;
+	type MaxTitleLength = ConstU32<256>;
+	type CommunityProvider = pallet_proposal::Community<Runtime, GrantsInstance>;
+	// The council is the proposal committee, its members are managed by `CouncilMembership`.
+	type CommitteeProvider = CouncilMembership;
+	type CommitteeIsExternal = ConstBool<true>;
+	type BudgetPeriod = GrantsBudgetPeriod;
+	type BudgetRanking = GrantsBudgetRanking;
+	type BudgetAllocation = GrantsBudgetAllocation;
+	type RankingOrigin = EnsureRootOrHalfCouncil;
+	type MaxRoundProposals = MaxRoundProposals;
+}
+
+/// Configure the pallet-proposal in pallets/proposal for the Bounties program.
+impl pallet_proposal::Config<BountiesInstance> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type TimeDuration = BountyTimeDuration;
+	type CandidacyBond = CandidacyBond;
+	type TermDuration = TermDuration;
+	type DesiredMembers = DesiredMembers;
+	type ApplicationDeposit = ApplicationDeposit;
+	type VouchThreshold = VouchThreshold;
+	type AdmissionOrigin = EitherOfDiverse<
+		frame_system::EnsureRoot<AccountId>,
+		pallet_proposal::EnsureProposal<Runtime, BountiesInstance>,
+	>;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type MaxCallLength = MaxCallLength;
+	type OnProposalOutcome = ();
+	type MaxPayees = MaxPayees;
+	type Quorum = Quorum;
+	type SecretBallots = SecretBallots;
+	type CommitDuration = CommitDuration;
+	type RevealDuration = RevealDuration;
+	type CommitDeposit = CommitDeposit;
+	type AmendmentPolicy = AmendmentVotePolicy;
+	type MaxOpenProposals = MaxOpenProposals;
+	type SubmissionGap = SubmissionGap;
+	type ResubmissionCooldown = ResubmissionCooldown;
+	type UrgentVoteWindow = UrgentVoteWindow;
+	type UrgentVotePriority = UrgentVotePriority;
+	type MaxFreeVotes = MaxFreeVotes;
+	type Settlement =
+		pallet_proposal::SchedulerSettlement<Runtime, Scheduler, OriginCaller, BountiesInstance>;
+	type Preimages = Preimage;
+	type MaxTitleLength = ConstU32<256>;
+	type CommunityProvider = pallet_proposal::Community<Runtime, BountiesInstance>;
+	type CommitteeProvider = pallet_proposal::Committee<Runtime, BountiesInstance>;
+	type CommitteeIsExternal = ConstBool<false>;
+	type BudgetPeriod = ConstU32<0>;
+	type BudgetRanking = BountiesBudgetRanking;
+	type BudgetAllocation = BountiesBudgetAllocation;
+	type RankingOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxRoundProposals = MaxRoundProposals;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -311,9 +527,14 @@ construct_runtime!(
 		Balances: pallet_balances,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
+		Scheduler: pallet_scheduler,
+		Preimage: pallet_preimage,
+		Council: pallet_collective::<Instance1>,
+		CouncilMembership: pallet_membership::<Instance1>,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
-		Proposal: pallet_proposal,
+		Grants: pallet_proposal::<Instance1>,
+		Bounties: pallet_proposal::<Instance2>,
 	}
 );
 
@@ -333,6 +554,10 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_proposal::CheckProposalRate<Runtime, GrantsInstance>,
+	pallet_proposal::CheckProposalMembership<Runtime, GrantsInstance>,
+	pallet_proposal::CheckProposalRate<Runtime, BountiesInstance>,
+	pallet_proposal::CheckProposalMembership<Runtime, BountiesInstance>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -340,6 +565,26 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Whether the extrinsic is a committee vote which will be refunded its fees.
+fn is_free_vote(uxt: &UncheckedExtrinsic) -> bool {
+	let who = match &uxt.signature {
+		Some((address, _, _)) => {
+			match <Runtime as frame_system::Config>::Lookup::lookup(address.clone()) {
+				Ok(who) => who,
+				Err(_) => return false,
+			}
+		},
+		None => return false,
+	};
+	match &uxt.function {
+		RuntimeCall::Grants(pallet_proposal::Call::approve_proposal { proposal, .. }) =>
+			Grants::is_free_vote(&who, *proposal),
+		RuntimeCall::Bounties(pallet_proposal::Call::approve_proposal { proposal, .. }) =>
+			Bounties::is_free_vote(&who, *proposal),
+		_ => false,
+	}
+}
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -485,13 +730,24 @@ impl_runtime_apis! {
 			uxt: <Block as BlockT>::Extrinsic,
 			len: u32,
 		) -> pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo<Balance> {
-			TransactionPayment::query_info(uxt, len)
+			// Free committee votes are charged up front and refunded after dispatch.
+			let free_vote = is_free_vote(&uxt);
+			let mut info = TransactionPayment::query_info(uxt, len);
+			if free_vote {
+				info.partial_fee = 0;
+			}
+			info
 		}
 		fn query_fee_details(
 			uxt: <Block as BlockT>::Extrinsic,
 			len: u32,
 		) -> pallet_transaction_payment::FeeDetails<Balance> {
-			TransactionPayment::query_fee_details(uxt, len)
+			let free_vote = is_free_vote(&uxt);
+			let mut details = TransactionPayment::query_fee_details(uxt, len);
+			if free_vote {
+				details.inclusion_fee = None;
+			}
+			details
 		}
 	}
 
@@ -512,6 +768,76 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_proposal_runtime_api::ProposalApi<Block, AccountId, Hash, FundingProgram>
+		for Runtime
+	{
+		fn proposals_by_proposer(
+			program: FundingProgram,
+			who: AccountId,
+			start: pallet_proposal::ProposalIndex,
+			limit: u32,
+		) -> Vec<(pallet_proposal::ProposalIndex, Hash)> {
+			match program {
+				FundingProgram::Grants =>
+					Grants::page(Grants::proposals_by_proposer(who), start, limit),
+				FundingProgram::Bounties =>
+					Bounties::page(Bounties::proposals_by_proposer(who), start, limit),
+			}
+		}
+		fn proposals_by_beneficiary(
+			program: FundingProgram,
+			who: AccountId,
+			start: pallet_proposal::ProposalIndex,
+			limit: u32,
+		) -> Vec<(pallet_proposal::ProposalIndex, Hash)> {
+			match program {
+				FundingProgram::Grants =>
+					Grants::page(Grants::proposals_by_beneficiary(who), start, limit),
+				FundingProgram::Bounties =>
+					Bounties::page(Bounties::proposals_by_beneficiary(who), start, limit),
+			}
+		}
+		fn proposals_by_status(
+			program: FundingProgram,
+			status: pallet_proposal::ProposalStatus,
+			start: pallet_proposal::ProposalIndex,
+			limit: u32,
+		) -> Vec<(pallet_proposal::ProposalIndex, Hash)> {
+			match program {
+				FundingProgram::Grants =>
+					Grants::page(Grants::proposals_by_status(status), start, limit),
+				FundingProgram::Bounties =>
+					Bounties::page(Bounties::proposals_by_status(status), start, limit),
+			}
+		}
+	}
+
+	impl pallet_proposal_runtime_api::DonationApi<
+		Block,
+		AccountId,
+		Balance,
+		Hash,
+		BlockNumber,
+		FundingProgram,
+	> for Runtime
+	{
+		fn donations(
+			program: FundingProgram,
+			who: AccountId,
+		) -> Vec<pallet_proposal::Donation<Balance, Hash, BlockNumber>> {
+			match program {
+				FundingProgram::Grants => Grants::donations(who),
+				FundingProgram::Bounties => Bounties::donations(who),
+			}
+		}
+		fn top_donors(program: FundingProgram, limit: u32) -> Vec<(AccountId, Balance)> {
+			match program {
+				FundingProgram::Grants => Grants::top_donors(limit),
+				FundingProgram::Bounties => Bounties::top_donors(limit),
+			}
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (